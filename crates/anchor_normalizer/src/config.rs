@@ -0,0 +1,95 @@
+//! Normalization configuration
+//!
+//! Options that tune how programs are normalized and validated.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Account types from common external crates that are always treated as known
+pub const BUILTIN_ACCOUNT_TYPES: &[&str] = &[
+    "TokenAccount",
+    "Mint",
+    "Metadata",
+    "MasterEditionAccount",
+    "TokenRecord",
+];
+
+/// Field names that get an inferred signer constraint by default
+pub const DEFAULT_AUTHORITY_FIELD_NAMES: &[&str] = &["authority", "owner", "admin"];
+
+/// Configuration for normalizing a program
+#[derive(Debug, Clone)]
+pub struct NormalizeConfig {
+    /// Extra account type names treated as known (e.g., from external crates)
+    pub known_types: Vec<String>,
+
+    /// Report an info issue for each instruction whose body couldn't be analyzed
+    pub warn_unknown_bodies: bool,
+
+    /// Program name used when neither a module nor the source path yields one
+    pub default_program_name: Option<String>,
+
+    /// Names of `Signer` fields inferred to require a signature
+    pub authority_field_names: Vec<String>,
+}
+
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            known_types: Vec::new(),
+            warn_unknown_bodies: false,
+            default_program_name: None,
+            authority_field_names: DEFAULT_AUTHORITY_FIELD_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl NormalizeConfig {
+    /// Create a configuration with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add extra known account type names
+    pub fn with_known_types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.known_types.extend(types.into_iter().map(Into::into));
+        self
+    }
+
+    /// Report instructions whose body couldn't be analyzed
+    pub fn with_warn_unknown_bodies(mut self, warn: bool) -> Self {
+        self.warn_unknown_bodies = warn;
+        self
+    }
+
+    /// Set the fallback program name
+    pub fn with_default_program_name(mut self, name: impl Into<String>) -> Self {
+        self.default_program_name = Some(name.into());
+        self
+    }
+
+    /// Add field names treated as authorities
+    pub fn with_authority_field_names<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.authority_field_names
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Check if a type name is in the built-in or configured allow-list
+    pub fn is_known_type(&self, name: &str) -> bool {
+        BUILTIN_ACCOUNT_TYPES.contains(&name) || self.known_types.iter().any(|t| t == name)
+    }
+}
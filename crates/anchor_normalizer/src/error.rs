@@ -1,3 +1,4 @@
+use alloc::string::String;
 use thiserror::Error;
 
 /// Errors that can occur during Anchor program normalization
@@ -19,10 +20,18 @@ pub enum NormalizeError {
     #[error("Missing information: {0}")]
     MissingInfo(String),
 
+    /// Error encoding a program
+    #[error("Encoding error: {0}")]
+    Encode(String),
+
+    /// Error decoding a serialized program
+    #[error("Decoding error: {0}")]
+    Decode(String),
+
     /// Other error
     #[error("Normalization error: {0}")]
     Other(String),
 }
 
 /// Result type for normalization operations
-pub type Result<T> = std::result::Result<T, NormalizeError>;
+pub type Result<T> = core::result::Result<T, NormalizeError>;
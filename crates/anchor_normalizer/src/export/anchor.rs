@@ -0,0 +1,354 @@
+//! Anchor source regeneration
+//!
+//! Emits Anchor Rust source for a normalized program: the program module
+//! with instruction signatures, the `#[derive(Accounts)]` structs with their
+//! constraints, the `#[account]` data structs and the `#[error_code]` enums.
+//! Instruction bodies are not modelled, so each handler body is `todo!()`.
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Write;
+
+use crate::model::{
+    BumpSource, NormalizedAccountField, NormalizedAccountStruct, NormalizedErrorEnum,
+    NormalizedItemKind, NormalizedModule, NormalizedProgram, NormalizedRawAccount, PdaInfo,
+    SeedElement,
+};
+
+/// Generate Anchor source for a normalized program
+///
+/// Items are written in the program's [`item_order`], so the output keeps
+/// the source layout; items missing from it follow in the order modules,
+/// account structs, raw accounts, error enums. Inferred constraints are
+/// left out, so the output only carries what the source spelled out. PDA
+/// constraints are rebuilt from the structured [`PdaInfo`] when inference
+/// has run.
+///
+/// # Arguments
+///
+/// * `program` - The normalized program
+///
+/// # Returns
+///
+/// Rust source that parses back to an equivalent program
+pub fn to_anchor_source(program: &NormalizedProgram) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "use anchor_lang::prelude::*;").unwrap();
+
+    let mut modules = Pending::new(&program.modules, |m| &m.name);
+    let mut account_structs = Pending::new(&program.account_structs, |a| &a.name);
+    let mut raw_accounts = Pending::new(&program.raw_accounts, |a| &a.name);
+    let mut error_enums = Pending::new(&program.error_enums, |e| &e.name);
+
+    for item in &program.item_order {
+        match item.kind {
+            NormalizedItemKind::Module => {
+                if let Some(module) = modules.take(&item.name) {
+                    out.push('\n');
+                    write_module(&mut out, module);
+                }
+            }
+            NormalizedItemKind::AccountStruct => {
+                if let Some(account) = account_structs.take(&item.name) {
+                    out.push('\n');
+                    write_account_struct(&mut out, account);
+                }
+            }
+            NormalizedItemKind::RawAccount => {
+                if let Some(account) = raw_accounts.take(&item.name) {
+                    out.push('\n');
+                    write_raw_account(&mut out, account);
+                }
+            }
+            NormalizedItemKind::ErrorEnum => {
+                if let Some(error_enum) = error_enums.take(&item.name) {
+                    out.push('\n');
+                    write_error_enum(&mut out, error_enum);
+                }
+            }
+        }
+    }
+
+    for module in modules.rest() {
+        out.push('\n');
+        write_module(&mut out, module);
+    }
+
+    for account in account_structs.rest() {
+        out.push('\n');
+        write_account_struct(&mut out, account);
+    }
+
+    for account in raw_accounts.rest() {
+        out.push('\n');
+        write_raw_account(&mut out, account);
+    }
+
+    for error_enum in error_enums.rest() {
+        out.push('\n');
+        write_error_enum(&mut out, error_enum);
+    }
+
+    out
+}
+
+/// Items of one kind that haven't been written yet
+struct Pending<'a, T> {
+    items: &'a [T],
+    written: Vec<bool>,
+    name: fn(&T) -> &str,
+}
+
+impl<'a, T> Pending<'a, T> {
+    fn new(items: &'a [T], name: fn(&T) -> &str) -> Self {
+        Self {
+            items,
+            written: vec![false; items.len()],
+            name,
+        }
+    }
+
+    /// Take the first unwritten item with the given name
+    fn take(&mut self, name: &str) -> Option<&'a T> {
+        let index = self
+            .items
+            .iter()
+            .zip(&self.written)
+            .position(|(item, written)| !written && (self.name)(item) == name)?;
+        self.written[index] = true;
+        Some(&self.items[index])
+    }
+
+    /// Get the items never taken, in their original order
+    fn rest(self) -> impl Iterator<Item = &'a T> {
+        self.items
+            .iter()
+            .zip(self.written)
+            .filter(|(_, written)| !written)
+            .map(|(item, _)| item)
+    }
+}
+
+/// Write the `#[program]` module with stub instruction handlers
+fn write_module(out: &mut String, module: &NormalizedModule) {
+    write_docs(out, "", module.documentation.as_deref());
+    writeln!(out, "#[program]").unwrap();
+    writeln!(
+        out,
+        "{}mod {} {{",
+        visibility(&module.visibility),
+        module.name
+    )
+    .unwrap();
+    writeln!(out, "    use super::*;").unwrap();
+
+    for instruction in &module.instructions {
+        out.push('\n');
+        write_docs(out, "    ", instruction.documentation.as_deref());
+        if instruction.is_interface {
+            writeln!(out, "    #[interface]").unwrap();
+        }
+
+        let params: Vec<String> = instruction
+            .parameters
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.ty))
+            .collect();
+        let return_type = instruction
+            .return_type
+            .as_deref()
+            .map(|ty| format!(" -> {}", ty))
+            .unwrap_or_default();
+
+        writeln!(
+            out,
+            "    {}fn {}({}){} {{",
+            visibility(&instruction.visibility),
+            instruction.name,
+            params.join(", "),
+            return_type
+        )
+        .unwrap();
+        writeln!(out, "        todo!()").unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+}
+
+/// Write a `#[derive(Accounts)]` struct
+fn write_account_struct(out: &mut String, account: &NormalizedAccountStruct) {
+    let lifetime = if account.fields.iter().any(|f| f.ty.contains("'info")) {
+        "<'info>"
+    } else {
+        ""
+    };
+
+    write_docs(out, "", account.documentation.as_deref());
+    writeln!(out, "#[derive(Accounts)]").unwrap();
+    if !account.instruction_args.is_empty() {
+        let args: Vec<String> = account
+            .instruction_args
+            .iter()
+            .map(|arg| format!("{}: {}", arg.name, arg.ty))
+            .collect();
+        writeln!(out, "#[instruction({})]", args.join(", ")).unwrap();
+    }
+    let where_clause = account
+        .where_clause
+        .as_deref()
+        .map(|clause| format!(" {}", clause))
+        .unwrap_or_default();
+    writeln!(
+        out,
+        "{}struct {}{}{} {{",
+        visibility(&account.visibility),
+        account.name,
+        lifetime,
+        where_clause
+    )
+    .unwrap();
+
+    for field in &account.fields {
+        write_docs(out, "    ", field.documentation.as_deref());
+        let constraints = field_constraints(field);
+        if !constraints.is_empty() {
+            writeln!(out, "    #[account({})]", constraints.join(", ")).unwrap();
+        }
+        writeln!(out, "    pub {}: {},", field.name, field.ty).unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+}
+
+/// Render the source constraints of a field, in their original order
+fn field_constraints(field: &NormalizedAccountField) -> Vec<String> {
+    let pda = field.inferred_info.pda.as_ref();
+    let mut constraints = Vec::new();
+
+    for constraint in field.constraints.iter().filter(|c| !c.is_inferred) {
+        let name = constraint.constraint_type.as_str();
+        match (name, pda) {
+            // Rebuilt together from the PDA info at the seeds position
+            ("seeds", Some(pda)) => constraints.extend(pda_constraints(pda)),
+            ("bump" | "seeds::program", Some(_)) => {}
+            _ => {
+                let mut rendered = match &constraint.value {
+                    Some(value) => format!("{} = {}", name, value),
+                    None => String::from(name),
+                };
+                if let Some(error_code) = &constraint.error_code {
+                    write!(rendered, " @ {}", error_code).unwrap();
+                }
+                constraints.push(rendered);
+            }
+        }
+    }
+
+    constraints
+}
+
+/// Render the `seeds`, `bump` and `seeds::program` constraints of a PDA
+fn pda_constraints(pda: &PdaInfo) -> Vec<String> {
+    let seeds: Vec<String> = pda.seeds.iter().map(seed_source).collect();
+
+    let mut constraints = vec![format!("seeds = [{}]", seeds.join(", "))];
+    constraints.push(match &pda.bump_source {
+        BumpSource::Canonical => String::from("bump"),
+        BumpSource::Stored(expr) => format!("bump = {}", expr),
+    });
+    if let Some(program) = &pda.program {
+        constraints.push(format!("seeds::program = {}", program));
+    }
+
+    constraints
+}
+
+/// Render a seed element as a seeds array entry
+fn seed_source(seed: &SeedElement) -> String {
+    match seed {
+        SeedElement::Literal(bytes) => {
+            let mut literal = String::from("b\"");
+            for &byte in bytes {
+                match byte {
+                    b'"' => literal.push_str("\\\""),
+                    b'\\' => literal.push_str("\\\\"),
+                    0x20..=0x7e => literal.push(byte as char),
+                    _ => write!(literal, "\\x{:02x}", byte).unwrap(),
+                }
+            }
+            literal.push('"');
+            literal
+        }
+        SeedElement::AccountRef(name) => format!("{}.key().as_ref()", name),
+        SeedElement::Expr(expr) => expr.clone(),
+    }
+}
+
+/// Write an `#[account]` data struct
+fn write_raw_account(out: &mut String, account: &NormalizedRawAccount) {
+    write_docs(out, "", account.documentation.as_deref());
+    match &account.discriminator_override {
+        Some(bytes) => {
+            let bytes: Vec<String> = bytes.iter().map(|b| format!("{}", b)).collect();
+            writeln!(out, "#[account(discriminator = [{}])]", bytes.join(", ")).unwrap();
+        }
+        None => writeln!(out, "#[account]").unwrap(),
+    }
+    if account.derives_init_space {
+        writeln!(out, "#[derive(InitSpace)]").unwrap();
+    }
+    writeln!(
+        out,
+        "{}struct {} {{",
+        visibility(&account.visibility),
+        account.name
+    )
+    .unwrap();
+
+    for field in &account.fields {
+        write_docs(out, "    ", field.documentation.as_deref());
+        if let Some(max_len) = field.max_len_attribute() {
+            writeln!(out, "    #[{}]", max_len).unwrap();
+        }
+        writeln!(
+            out,
+            "    {}{}: {},",
+            visibility(&field.visibility),
+            field.name,
+            field.ty
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+}
+
+/// Write an `#[error_code]` enum
+fn write_error_enum(out: &mut String, error_enum: &NormalizedErrorEnum) {
+    writeln!(out, "#[error_code]").unwrap();
+    writeln!(out, "pub enum {} {{", error_enum.name).unwrap();
+    for (variant, message) in error_enum.variants.iter().zip(&error_enum.messages) {
+        if let Some(message) = message {
+            writeln!(out, "    #[msg({:?})]", message).unwrap();
+        }
+        writeln!(out, "    {},", variant).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// Write documentation as `///` lines
+fn write_docs(out: &mut String, indent: &str, docs: Option<&str>) {
+    for line in docs.into_iter().flat_map(str::lines) {
+        writeln!(out, "{}/// {}", indent, line.trim()).unwrap();
+    }
+}
+
+/// A visibility followed by a space, or nothing for private items
+fn visibility(visibility: &str) -> String {
+    if visibility.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", visibility)
+    }
+}
@@ -0,0 +1,81 @@
+//! Constraint matrix export
+//!
+//! Renders each account struct as a table of fields against the constraint
+//! flags that matter most when reviewing access control.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::model::{FieldKind, NormalizedAccountField, NormalizedProgram};
+
+/// Constraint flag columns, in output order
+pub const MATRIX_FLAGS: &[&str] = &["mut", "signer", "init", "close", "has_one", "seeds"];
+
+/// Generate the constraint matrix of a program as CSV
+///
+/// Emits a header row followed by one row per field, in account struct
+/// order. Flag cells are `true` or `false`.
+///
+/// # Arguments
+///
+/// * `program` - The normalized program
+///
+/// # Returns
+///
+/// CSV with `account,field` columns followed by [`MATRIX_FLAGS`]
+pub fn to_constraint_matrix_csv(program: &NormalizedProgram) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "account,field,{}", MATRIX_FLAGS.join(",")).unwrap();
+    for account in &program.account_structs {
+        for field in &account.fields {
+            let flags: Vec<&str> = field_flags(field)
+                .iter()
+                .map(|&set| if set { "true" } else { "false" })
+                .collect();
+            writeln!(out, "{},{},{}", account.name, field.name, flags.join(",")).unwrap();
+        }
+    }
+
+    out
+}
+
+/// Generate the constraint matrix of a program as Markdown
+///
+/// Emits one table per account struct under a `###` heading, marking set
+/// flags with `x`.
+pub fn to_constraint_matrix_markdown(program: &NormalizedProgram) -> String {
+    let mut out = String::new();
+
+    for (idx, account) in program.account_structs.iter().enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        writeln!(out, "### {}\n", account.name).unwrap();
+        writeln!(out, "| field | {} |", MATRIX_FLAGS.join(" | ")).unwrap();
+        writeln!(out, "|---{}|", "|---".repeat(MATRIX_FLAGS.len())).unwrap();
+
+        for field in &account.fields {
+            let flags: Vec<&str> = field_flags(field)
+                .iter()
+                .map(|&set| if set { "x" } else { " " })
+                .collect();
+            writeln!(out, "| {} | {} |", field.name, flags.join(" | ")).unwrap();
+        }
+    }
+
+    out
+}
+
+/// The value of each [`MATRIX_FLAGS`] column for a field
+fn field_flags(field: &NormalizedAccountField) -> [bool; 6] {
+    let info = &field.inferred_info;
+    [
+        info.requires_mut,
+        info.requires_signer || info.kind == FieldKind::Signer,
+        info.is_initialized,
+        field.find_constraint("close").is_some(),
+        field.find_constraint("has_one").is_some(),
+        info.pda.is_some() || field.find_constraint("seeds").is_some(),
+    ]
+}
@@ -0,0 +1,13 @@
+//! Exporters for normalized programs
+//!
+//! Render a normalized program into formats consumed by other tools.
+
+pub mod anchor;
+pub mod matrix;
+pub mod report;
+pub mod typescript;
+
+pub use anchor::to_anchor_source;
+pub use matrix::{to_constraint_matrix_csv, to_constraint_matrix_markdown};
+pub use report::to_markdown_report;
+pub use typescript::to_typescript;
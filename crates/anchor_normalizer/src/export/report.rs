@@ -0,0 +1,90 @@
+//! Markdown review report
+//!
+//! Renders a human-readable summary of a program with one section per
+//! instruction, listing the accounts it requires and how each is used.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use crate::model::{FieldKind, NormalizedInstruction, NormalizedProgram};
+
+/// Generate a Markdown report for a normalized program
+///
+/// Each instruction gets a `###` section with its arguments and a table
+/// of the accounts in its linked account struct, marking mutable, signer
+/// and initialized accounts with `x`. Instructions whose account struct
+/// cannot be resolved get a note instead of a table.
+///
+/// # Arguments
+///
+/// * `program` - The normalized program
+///
+/// # Returns
+///
+/// Markdown source for the report
+pub fn to_markdown_report(program: &NormalizedProgram) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "# {}", program.name).unwrap();
+    if let Some(docs) = &program.documentation {
+        writeln!(out, "\n{}", docs).unwrap();
+    }
+
+    for module in &program.modules {
+        writeln!(out, "\n## {}", module.name).unwrap();
+        for instruction in &module.instructions {
+            out.push('\n');
+            write_instruction_section(&mut out, program, instruction);
+        }
+    }
+
+    out
+}
+
+/// Write the section for one instruction
+fn write_instruction_section(
+    out: &mut String,
+    program: &NormalizedProgram,
+    instruction: &NormalizedInstruction,
+) {
+    writeln!(out, "### {}", instruction.name).unwrap();
+    if let Some(docs) = &instruction.documentation {
+        writeln!(out, "\n{}", docs).unwrap();
+    }
+
+    let args: Vec<String> = instruction
+        .parameters
+        .iter()
+        .filter(|p| !p.is_context)
+        .map(|p| alloc::format!("`{}: {}`", p.name, p.ty))
+        .collect();
+    if !args.is_empty() {
+        writeln!(out, "\nArguments: {}", args.join(", ")).unwrap();
+    }
+
+    let Some(account) = instruction
+        .account_struct_name
+        .as_deref()
+        .and_then(|name| program.find_account_struct(name))
+    else {
+        writeln!(out, "\nNo account struct resolved.").unwrap();
+        return;
+    };
+
+    writeln!(out, "\nAccounts (`{}`):\n", account.name).unwrap();
+    writeln!(out, "| account | mut | signer | init |").unwrap();
+    writeln!(out, "|---|---|---|---|").unwrap();
+    for field in &account.fields {
+        let info = &field.inferred_info;
+        let flags = [
+            info.requires_mut,
+            info.requires_signer || info.kind == FieldKind::Signer,
+            info.is_initialized,
+        ];
+        let cells: Vec<&str> = flags
+            .iter()
+            .map(|&set| if set { "x" } else { " " })
+            .collect();
+        writeln!(out, "| {} | {} |", field.name, cells.join(" | ")).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+//! TypeScript type definitions export
+//!
+//! Emits TypeScript interfaces for raw accounts and instruction arguments,
+//! following the type mapping used by Anchor's TypeScript client.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::fmt::Write;
+
+use crate::model::{NormalizedInstruction, NormalizedProgram, NormalizedRawAccount};
+use crate::normalization::expr;
+
+/// Generate TypeScript interfaces for a normalized program
+///
+/// Emits one interface per raw account and one `<Instruction>Args`
+/// interface per instruction that takes non-context arguments.
+///
+/// # Arguments
+///
+/// * `program` - The normalized program
+///
+/// # Returns
+///
+/// TypeScript source containing the interface definitions
+pub fn to_typescript(program: &NormalizedProgram) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "import {{ PublicKey }} from \"@solana/web3.js\";").unwrap();
+    writeln!(out, "import BN from \"bn.js\";").unwrap();
+
+    for account in &program.raw_accounts {
+        out.push('\n');
+        write_account_interface(&mut out, account);
+    }
+
+    for module in &program.modules {
+        for instruction in &module.instructions {
+            if instruction.parameters.iter().any(|p| !p.is_context) {
+                out.push('\n');
+                write_args_interface(&mut out, instruction);
+            }
+        }
+    }
+
+    out
+}
+
+/// Write the interface for a raw account
+fn write_account_interface(out: &mut String, account: &NormalizedRawAccount) {
+    writeln!(out, "export interface {} {{", account.name).unwrap();
+    for field in &account.fields {
+        writeln!(
+            out,
+            "  {}: {};",
+            camel_case(&field.name),
+            ts_type(&field.ty)
+        )
+        .unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// Write the arguments interface for an instruction
+fn write_args_interface(out: &mut String, instruction: &NormalizedInstruction) {
+    writeln!(
+        out,
+        "export interface {}Args {{",
+        pascal_case(&instruction.name)
+    )
+    .unwrap();
+    for param in instruction.parameters.iter().filter(|p| !p.is_context) {
+        writeln!(
+            out,
+            "  {}: {};",
+            camel_case(&param.name),
+            ts_type(&param.ty)
+        )
+        .unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+}
+
+/// Map a Rust type to its TypeScript equivalent
+fn ts_type(ty: &str) -> String {
+    let ty = ty.trim();
+
+    // Fixed-size arrays: [T; N]
+    if let Some(inner) = ty.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let element = expr::split_top_level_type(inner, ';');
+        if let Some(element) = element.first() {
+            return format!("{}[]", ts_type(element));
+        }
+    }
+
+    let (name, args) = expr::split_generic_type(ty);
+    match (name, args.as_slice()) {
+        ("Vec", [inner]) => format!("{}[]", ts_type(inner)),
+        ("Option", [inner]) => format!("{} | null", ts_type(inner)),
+        ("Box", [inner]) => ts_type(inner),
+        ("u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" | "f64", _) => "number".to_string(),
+        ("u64" | "u128" | "i64" | "i128", _) => "BN".to_string(),
+        ("bool", _) => "boolean".to_string(),
+        ("String" | "str" | "&str", _) => "string".to_string(),
+        ("Pubkey", _) => "PublicKey".to_string(),
+        (other, _) => other.to_string(),
+    }
+}
+
+/// Convert a snake_case name to camelCase
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Convert a snake_case name to PascalCase
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
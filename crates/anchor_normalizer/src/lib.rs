@@ -1,12 +1,28 @@
 // In lib.rs
+//! The model types only need `alloc`. The normalization passes live behind
+//! the default `std` feature; disable it to use the model under `no_std`.
+//! Serde derives and [`model::NormalizedProgram::fingerprint`] live behind
+//! the default `serde` feature. The optional `bincode` feature adds a
+//! compact binary encoding, [`model::NormalizedProgram::to_bincode`].
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
+
+pub mod config;
 pub mod error;
+pub mod export;
 pub mod model; // This makes the model module public
 pub mod normalization;
 
+#[cfg(feature = "std")]
 use crate::error::Result;
+pub use config::NormalizeConfig;
 pub use error::NormalizeError;
 
-use crate::normalization::normalize_program;
+#[cfg(feature = "std")]
+use crate::normalization::{normalize_each_program_with_config, normalize_program_with_config};
+#[cfg(feature = "std")]
 use anchor_parser::model::Program;
 
 /// Normalize an Anchor program
@@ -21,8 +37,44 @@ use anchor_parser::model::Program;
 /// # Returns
 ///
 /// A normalized program model or an error if normalization fails
+#[cfg(feature = "std")]
 pub fn normalize(program: &Program) -> Result<model::NormalizedProgram> {
-    normalize_program(program)
+    normalize_with_config(program, &NormalizeConfig::default())
+}
+
+/// Normalize an Anchor program with custom configuration
+///
+/// # Arguments
+///
+/// * `program` - The parsed Anchor program
+/// * `config` - Normalization options (e.g., extra known account types)
+///
+/// # Returns
+///
+/// A normalized program model or an error if normalization fails
+#[cfg(feature = "std")]
+pub fn normalize_with_config(
+    program: &Program,
+    config: &NormalizeConfig,
+) -> Result<model::NormalizedProgram> {
+    normalize_program_with_config(program, config)
+}
+
+/// Normalize each `#[program]` module of an Anchor program separately
+///
+/// Produces one normalized program per module, each carrying only the
+/// account structs (and the raw accounts they wrap) its instructions use.
+///
+/// # Arguments
+///
+/// * `program` - The parsed Anchor program
+///
+/// # Returns
+///
+/// One normalized program per module or an error if normalization fails
+#[cfg(feature = "std")]
+pub fn normalize_each(program: &Program) -> Result<Vec<model::NormalizedProgram>> {
+    normalize_each_program_with_config(program, &NormalizeConfig::default())
 }
 
 // Re-export all relevant types for convenience
@@ -2,10 +2,21 @@
 //!
 //! Defines normalized account structures and related types
 
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::model::discriminator::discriminator;
+use crate::model::NormalizedParameter;
+use crate::normalization::{expr, space};
+
 /// Normalized account structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedAccountStruct {
     /// Account structure name
     pub name: String,
@@ -18,10 +29,18 @@ pub struct NormalizedAccountStruct {
 
     /// Account structure documentation
     pub documentation: Option<String>,
+
+    /// Instruction arguments declared with `#[instruction(...)]`
+    pub instruction_args: Vec<NormalizedParameter>,
+
+    /// The struct's `where` clause as written (e.g., "where T: Owner")
+    pub where_clause: Option<String>,
 }
 
 /// Normalized account field
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedAccountField {
     /// Field name
     pub name: String,
@@ -40,7 +59,9 @@ pub struct NormalizedAccountField {
 }
 
 /// Normalized constraint
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedConstraint {
     /// Constraint type
     pub constraint_type: String,
@@ -50,10 +71,15 @@ pub struct NormalizedConstraint {
 
     /// Whether this constraint was inferred (not in source)
     pub is_inferred: bool,
+
+    /// Custom error from a trailing `@ Enum::Variant`
+    pub error_code: Option<String>,
 }
 
 /// Inferred semantic information for fields
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct InferredFieldInfo {
     /// Whether field must be mutable
     pub requires_mut: bool,
@@ -66,10 +92,180 @@ pub struct InferredFieldInfo {
 
     /// Related account (if any)
     pub related_account: Option<String>,
+
+    /// Program referenced by a `Program<'info, X>` or `Interface<'info, X>`
+    /// field, or owning the token account of an `InterfaceAccount` field
+    pub program_kind: Option<ProgramKind>,
+
+    /// Program derived address information, for fields with `seeds`
+    pub pda: Option<PdaInfo>,
+
+    /// Program required to own the account, from an `owner` constraint
+    pub owner_program: Option<String>,
+
+    /// Address the account is pinned to, from an `address` constraint
+    pub fixed_address: Option<String>,
+
+    /// Account size in bytes, when the `space` constraint can be evaluated
+    pub resolved_space: Option<usize>,
+
+    /// Account paying for a `realloc`, from `realloc::payer`
+    pub realloc_payer: Option<String>,
+
+    /// Whether reallocated space is zero-initialized, from `realloc::zero`
+    pub realloc_zero: bool,
+
+    /// Classification of the field's account type
+    pub kind: FieldKind,
+}
+
+/// Classification of an account field
+///
+/// Serialized internally tagged as `{"kind": "AssociatedTokenAccount", "mint": ..., "authority": ...}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
+pub enum FieldKind {
+    /// Program-owned account data (`Account<'info, T>`, `AccountLoader<'info, T>`)
+    Account,
+
+    /// Associated token account defined by `associated_token::*` constraints
+    AssociatedTokenAccount { mint: String, authority: String },
+
+    /// Transaction signer (`Signer<'info>`)
+    Signer,
+
+    /// Program account (`Program<'info, T>`)
+    Program,
+
+    /// System-owned account (`SystemAccount<'info>`)
+    SystemAccount,
+
+    /// Account the program doesn't check (`UncheckedAccount<'info>`, `AccountInfo<'info>`)
+    Unchecked,
+
+    /// Anything not classified above
+    #[default]
+    Other,
+}
+
+impl FieldKind {
+    /// Classify a field from its type and constraints
+    ///
+    /// A field with both `associated_token::mint` and
+    /// `associated_token::authority` constraints is an associated token
+    /// account, whatever its wrapper type.
+    pub fn from_field(field: &NormalizedAccountField) -> Self {
+        let constraint_value = |name: &str| {
+            field
+                .find_constraint(name)
+                .and_then(|c| c.value.as_deref())
+                .map(expr::compact_expr)
+        };
+
+        if let (Some(mint), Some(authority)) = (
+            constraint_value("associated_token::mint"),
+            constraint_value("associated_token::authority"),
+        ) {
+            return FieldKind::AssociatedTokenAccount { mint, authority };
+        }
+
+        if field.wrapped_account_type().is_some() {
+            return FieldKind::Account;
+        }
+
+        match expr::split_generic_type(&field.ty).0 {
+            "Signer" => FieldKind::Signer,
+            "Program" | "Interface" => FieldKind::Program,
+            "SystemAccount" => FieldKind::SystemAccount,
+            "UncheckedAccount" | "AccountInfo" => FieldKind::Unchecked,
+            _ => FieldKind::Other,
+        }
+    }
+}
+
+/// Program derived address information for a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct PdaInfo {
+    /// Structured seed elements, in order
+    pub seeds: Vec<SeedElement>,
+
+    /// Where the bump seed comes from
+    pub bump_source: BumpSource,
+
+    /// Program the address derives from, from `seeds::program` (defaults to this program)
+    pub program: Option<String>,
+
+    /// Raw account field a stored bump reads, as (account, field)
+    ///
+    /// Set when `bump = vault.bump` names a field of the account data
+    /// wrapped by the struct's `vault` field.
+    pub stored_bump_field: Option<(String, String)>,
+}
+
+/// Source of a PDA's bump seed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
+pub enum BumpSource {
+    /// Bare `bump`: the canonical bump is found at runtime
+    Canonical,
+
+    /// `bump = expr`: a bump stored elsewhere (e.g., `state.bump`)
+    Stored(String),
+}
+
+/// A single element of a PDA seeds list
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "value"))]
+pub enum SeedElement {
+    /// Literal bytes (e.g., `b"vault"`)
+    Literal(Vec<u8>),
+
+    /// The key of another account in the struct (e.g., `authority.key().as_ref()`)
+    AccountRef(String),
+
+    /// Any other expression, kept as source text
+    Expr(String),
+}
+
+/// Well-known programs referenced by `Program<'info, X>` and `Interface<'info, X>` fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub enum ProgramKind {
+    /// The system program (`Program<'info, System>`)
+    System,
+
+    /// The legacy SPL token program (`Program<'info, Token>`)
+    Token,
+
+    /// The Token-2022 program (`Program<'info, Token2022>`), which has a
+    /// different program ID from legacy SPL Token
+    Token2022,
+
+    /// Either token program, through `anchor_spl::token_interface`
+    /// (`Interface<'info, TokenInterface>`); the program ID is only known
+    /// at runtime
+    TokenInterface,
+
+    /// The associated token account program (`Program<'info, AssociatedToken>`)
+    AssociatedToken,
+
+    /// Any other program, by type name
+    Other(String),
 }
 
 /// Normalized raw account
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedRawAccount {
     /// Account name
     pub name: String,
@@ -82,10 +278,18 @@ pub struct NormalizedRawAccount {
 
     /// Account documentation
     pub documentation: Option<String>,
+
+    /// Whether the account has #[derive(InitSpace)]
+    pub derives_init_space: bool,
+
+    /// Explicit discriminator from `#[account(discriminator = ...)]`
+    pub discriminator_override: Option<Vec<u8>>,
 }
 
 /// Normalized raw account field
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedRawField {
     /// Field name
     pub name: String,
@@ -98,6 +302,10 @@ pub struct NormalizedRawField {
 
     /// Field documentation
     pub documentation: Option<String>,
+
+    /// Maximum lengths of a `Vec`/`String` field, outermost first,
+    /// from `#[max_len(N, ...)]`
+    pub max_len: Vec<usize>,
 }
 
 impl NormalizedAccountStruct {
@@ -108,6 +316,8 @@ impl NormalizedAccountStruct {
             visibility: visibility.into(),
             fields: Vec::new(),
             documentation: None,
+            instruction_args: Vec::new(),
+            where_clause: None,
         }
     }
 
@@ -116,11 +326,42 @@ impl NormalizedAccountStruct {
         self.fields.push(field);
     }
 
+    /// Add an `#[instruction(...)]` argument to the account struct
+    pub fn add_instruction_arg(&mut self, arg: NormalizedParameter) {
+        self.instruction_args.push(arg);
+    }
+
     /// Find a field by name
     pub fn find_field(&self, name: &str) -> Option<&NormalizedAccountField> {
         self.fields.iter().find(|f| f.name == name)
     }
 
+    /// Get fields whose constraints change lamports (init, init_if_needed, realloc, close)
+    pub fn rent_affecting_fields(&self) -> Vec<&NormalizedAccountField> {
+        const RENT_CONSTRAINTS: &[&str] = &["init", "init_if_needed", "realloc", "close"];
+
+        self.fields
+            .iter()
+            .filter(|field| {
+                field
+                    .constraints
+                    .iter()
+                    .any(|c| RENT_CONSTRAINTS.contains(&c.constraint_type.as_str()))
+            })
+            .collect()
+    }
+
+    /// Get fields without any explicit constraint
+    ///
+    /// Constraints added by inference don't count, so these are the fields
+    /// written without an `#[account(...)]` attribute (or with an empty one).
+    pub fn unconstrained_fields(&self) -> Vec<&NormalizedAccountField> {
+        self.fields
+            .iter()
+            .filter(|field| field.constraints.iter().all(|c| c.is_inferred))
+            .collect()
+    }
+
     /// Set the documentation
     pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
         self.documentation = Some(docs.into());
@@ -141,6 +382,14 @@ impl NormalizedAccountField {
                 requires_signer: false,
                 is_initialized: false,
                 related_account: None,
+                program_kind: None,
+                pda: None,
+                owner_program: None,
+                fixed_address: None,
+                resolved_space: None,
+                realloc_payer: None,
+                realloc_zero: false,
+                kind: FieldKind::Other,
             },
         }
     }
@@ -157,6 +406,24 @@ impl NormalizedAccountField {
                     self.inferred_info.related_account = Some(value.clone());
                 }
             }
+            "owner" => {
+                if let Some(value) = &constraint.value {
+                    self.inferred_info.owner_program = Some(expr::compact_expr(value));
+                }
+            }
+            "address" => {
+                if let Some(value) = &constraint.value {
+                    self.inferred_info.fixed_address = Some(expr::compact_expr(value));
+                }
+            }
+            "realloc::payer" => {
+                if let Some(value) = &constraint.value {
+                    self.inferred_info.realloc_payer = Some(expr::compact_expr(value));
+                }
+            }
+            "realloc::zero" => {
+                self.inferred_info.realloc_zero = constraint.value.as_deref() == Some("true");
+            }
             _ => {}
         }
 
@@ -170,6 +437,41 @@ impl NormalizedAccountField {
             .find(|c| c.constraint_type == constraint_type)
     }
 
+    /// Get the account data type wrapped by this field's type
+    ///
+    /// Returns `Vault` for `Account<'info, Vault>`, `Box<Account<'info, Vault>>`
+    /// and `AccountLoader<'info, Vault>`.
+    pub fn wrapped_account_type(&self) -> Option<&str> {
+        expr::wrapped_account_type(&self.ty)
+    }
+
+    /// Get the program type of a `Program<'info, X>` or `Interface<'info, X>` field
+    pub fn program_type(&self) -> Option<&str> {
+        let (name, args) = expr::split_generic_type(&self.ty);
+        if name != "Program" && name != "Interface" {
+            return None;
+        }
+        args.last().copied().filter(|arg| !arg.starts_with('\''))
+    }
+
+    /// Check whether the field holds a token account or mint of either
+    /// token program (`InterfaceAccount<'info, TokenAccount>`, boxed or not)
+    pub fn is_token_interface_account(&self) -> bool {
+        let wrapper = match expr::split_generic_type(&self.ty) {
+            ("Box", args) => args
+                .first()
+                .map_or("", |inner| expr::split_generic_type(inner).0),
+            (name, _) => name,
+        };
+
+        wrapper == "InterfaceAccount"
+            && self.wrapped_account_type().is_some_and(|ty| {
+                ty.split("::")
+                    .any(|segment| segment.trim() == "token_interface")
+                    || matches!(expr::last_segment(ty), "TokenAccount" | "Mint")
+            })
+    }
+
     /// Set the documentation
     pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
         self.documentation = Some(docs.into());
@@ -188,6 +490,7 @@ impl NormalizedConstraint {
             constraint_type: constraint_type.into(),
             value: value.map(|v| v.into()),
             is_inferred,
+            error_code: None,
         }
     }
 
@@ -197,6 +500,7 @@ impl NormalizedConstraint {
             constraint_type: constraint_type.into(),
             value: None,
             is_inferred,
+            error_code: None,
         }
     }
 
@@ -210,8 +514,34 @@ impl NormalizedConstraint {
             constraint_type: constraint_type.into(),
             value: Some(value.into()),
             is_inferred,
+            error_code: None,
         }
     }
+
+    /// Builder method: with a custom error (e.g., `VaultError::TooSmall`)
+    pub fn with_error_code(mut self, error_code: impl Into<String>) -> Self {
+        self.error_code = Some(error_code.into());
+        self
+    }
+}
+
+impl NormalizedConstraint {
+    /// Check whether the constraint value uses one of an instruction's arguments
+    ///
+    /// True when a free identifier of the value (e.g., `amount` in
+    /// `8 + amount as usize`, but not in `state.amount`) names an argument
+    /// declared with `#[instruction(...)]` on `account`. Constraints can only
+    /// see those arguments, not the rest of the handler's parameters.
+    pub fn references_instruction_arg(&self, account: &NormalizedAccountStruct) -> bool {
+        let Some(value) = self.value.as_deref() else {
+            return false;
+        };
+        let value = expr::compact_expr(value);
+
+        expr::free_identifiers(&value)
+            .into_iter()
+            .any(|ident| account.instruction_args.iter().any(|arg| arg.name == ident))
+    }
 }
 
 impl InferredFieldInfo {
@@ -222,6 +552,53 @@ impl InferredFieldInfo {
             requires_signer: false,
             is_initialized: false,
             related_account: None,
+            program_kind: None,
+            pda: None,
+            owner_program: None,
+            fixed_address: None,
+            resolved_space: None,
+            realloc_payer: None,
+            realloc_zero: false,
+            kind: FieldKind::Other,
+        }
+    }
+}
+
+impl ProgramKind {
+    /// Classify a program type name (e.g., "System" or "anchor_spl::token::Token")
+    ///
+    /// Types under the `token_2022` namespace are classified as
+    /// [`ProgramKind::Token2022`] and types under `token_interface` as
+    /// [`ProgramKind::TokenInterface`], rather than legacy SPL Token.
+    pub fn from_type_name(name: &str) -> Self {
+        let in_namespace =
+            |namespace: &str| name.split("::").any(|segment| segment.trim() == namespace);
+        if in_namespace("token_2022") {
+            return ProgramKind::Token2022;
+        }
+        if in_namespace("token_interface") {
+            return ProgramKind::TokenInterface;
+        }
+
+        match expr::last_segment(name) {
+            "System" => ProgramKind::System,
+            "Token" => ProgramKind::Token,
+            "Token2022" => ProgramKind::Token2022,
+            "TokenInterface" => ProgramKind::TokenInterface,
+            "AssociatedToken" => ProgramKind::AssociatedToken,
+            other => ProgramKind::Other(other.to_string()),
+        }
+    }
+
+    /// The program's type name (e.g., "System" or the `Other` name)
+    pub fn name(&self) -> &str {
+        match self {
+            ProgramKind::System => "System",
+            ProgramKind::Token => "Token",
+            ProgramKind::Token2022 => "Token2022",
+            ProgramKind::TokenInterface => "TokenInterface",
+            ProgramKind::AssociatedToken => "AssociatedToken",
+            ProgramKind::Other(name) => name,
         }
     }
 }
@@ -234,6 +611,8 @@ impl NormalizedRawAccount {
             visibility: visibility.into(),
             fields: Vec::new(),
             documentation: None,
+            derives_init_space: false,
+            discriminator_override: None,
         }
     }
 
@@ -247,6 +626,60 @@ impl NormalizedRawAccount {
         self.fields.iter().find(|f| f.name == name)
     }
 
+    /// Compute the `INIT_SPACE` generated by #[derive(InitSpace)]
+    ///
+    /// Sums the field sizes, using `max_len` for `String`/`Vec` fields.
+    /// Returns `None` if the account doesn't derive `InitSpace` or a field's
+    /// size is unknown.
+    pub fn init_space(&self) -> Option<usize> {
+        if !self.derives_init_space {
+            return None;
+        }
+
+        self.layout_size()
+    }
+
+    /// Get the size of each field in declaration order
+    ///
+    /// Sizes follow the `InitSpace` rules; a field's size is `None` when
+    /// it can't be determined.
+    pub fn layout(&self) -> Vec<(&str, Option<usize>)> {
+        self.fields
+            .iter()
+            .map(|field| {
+                (
+                    field.name.as_str(),
+                    space::type_size(&field.ty, &field.max_len),
+                )
+            })
+            .collect()
+    }
+
+    /// Sum the [`layout`](Self::layout) field sizes
+    ///
+    /// Returns `None` if any field's size is unknown.
+    pub fn layout_size(&self) -> Option<usize> {
+        self.layout().into_iter().map(|(_, size)| size).sum()
+    }
+
+    /// Compute the Anchor account discriminator
+    ///
+    /// The first 8 bytes of `sha256("account:<StructName>")`.
+    pub fn discriminator(&self) -> [u8; 8] {
+        discriminator("account", &self.name)
+    }
+
+    /// Get the bytes that prefix the account data
+    ///
+    /// Uses the explicit override when present, which may be any length,
+    /// otherwise the computed [`discriminator`](Self::discriminator).
+    pub fn discriminator_bytes(&self) -> Vec<u8> {
+        match &self.discriminator_override {
+            Some(bytes) => bytes.clone(),
+            None => self.discriminator().to_vec(),
+        }
+    }
+
     /// Set the documentation
     pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
         self.documentation = Some(docs.into());
@@ -266,6 +699,7 @@ impl NormalizedRawField {
             ty: ty.into(),
             visibility: visibility.into(),
             documentation: None,
+            max_len: Vec::new(),
         }
     }
 
@@ -274,4 +708,13 @@ impl NormalizedRawField {
         self.documentation = Some(docs.into());
         self
     }
+
+    /// Get the `max_len(...)` attribute for the field, if it has lengths
+    pub fn max_len_attribute(&self) -> Option<String> {
+        if self.max_len.is_empty() {
+            return None;
+        }
+        let lengths: Vec<String> = self.max_len.iter().map(|len| len.to_string()).collect();
+        Some(alloc::format!("max_len({})", lengths.join(", ")))
+    }
 }
@@ -0,0 +1,15 @@
+//! Anchor discriminator computation
+//!
+//! Anchor prefixes instruction data and account data with the first eight
+//! bytes of `sha256("<namespace>:<name>")`.
+
+use alloc::format;
+use sha2::{Digest, Sha256};
+
+/// Compute an Anchor discriminator for a name in the given namespace
+pub(crate) fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let digest = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    bytes
+}
@@ -2,10 +2,23 @@
 //!
 //! Defines normalized instruction structures and related types
 
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+use crate::error::{NormalizeError, Result};
+use crate::model::discriminator::discriminator;
+use crate::model::{FieldKind, NormalizedProgram, SeedElement};
+use crate::normalization::{expr, seeds::parse_seeds};
+
 /// Normalized representation of an instruction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedInstruction {
     /// Instruction name
     pub name: String,
@@ -27,10 +40,24 @@ pub struct NormalizedInstruction {
 
     /// Instruction-level documentation
     pub documentation: Option<String>,
+
+    /// Whether the instruction is marked `#[interface]`
+    pub is_interface: bool,
+
+    /// Sysvars the body reads at runtime (e.g., "Clock")
+    pub uses_sysvars: Vec<String>,
+
+    /// Functions the body calls by name (e.g., "helper")
+    pub calls: Vec<String>,
+
+    /// Position of the instruction across all modules of the program
+    pub declaration_index: usize,
 }
 
 /// Normalized parameter for an instruction
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedParameter {
     /// Parameter name
     pub name: String,
@@ -40,10 +67,21 @@ pub struct NormalizedParameter {
 
     /// Whether this is a Context parameter
     pub is_context: bool,
+
+    /// Whether the argument is an `Option<...>`
+    pub is_optional: bool,
+
+    /// Parameter-level documentation
+    pub documentation: Option<String>,
 }
 
 /// Placeholder for instruction body semantics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Serialized adjacently tagged as `{"kind": "Basic", "operations": [...]}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", content = "operations"))]
 pub enum InstructionBody {
     /// Unknown implementation - will be completed when parser is enhanced
     Unknown,
@@ -53,10 +91,15 @@ pub enum InstructionBody {
 }
 
 /// Basic operation types that might be inferred
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Serialized internally tagged as `{"kind": "Transfer", "from": ..., "to": ...}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum BasicOperation {
     /// Logs a message
-    Log(String),
+    Log { message: String },
 
     /// Creates a new account
     Initialize { target: String, payer: String },
@@ -66,6 +109,12 @@ pub enum BasicOperation {
 
     /// Closes an account
     Close { target: String, refund_to: String },
+
+    /// Resizes an account; `payer` is `None` when `realloc::payer` is missing
+    Realloc {
+        target: String,
+        payer: Option<String>,
+    },
 }
 
 impl NormalizedInstruction {
@@ -79,6 +128,10 @@ impl NormalizedInstruction {
             account_struct_name: None,
             body: Some(InstructionBody::Unknown),
             documentation: None,
+            is_interface: false,
+            uses_sysvars: Vec::new(),
+            calls: Vec::new(),
+            declaration_index: 0,
         }
     }
 
@@ -111,6 +164,139 @@ impl NormalizedInstruction {
         self
     }
 
+    /// Mark whether the instruction is an interface instruction
+    pub fn with_interface(mut self, is_interface: bool) -> Self {
+        self.is_interface = is_interface;
+        self
+    }
+
+    /// Compute the Anchor instruction discriminator
+    ///
+    /// The first 8 bytes of `sha256("global:<instruction_name>")`.
+    pub fn discriminator(&self) -> [u8; 8] {
+        discriminator("global", &self.name)
+    }
+
+    /// Order the accounts this instruction initializes by seed dependencies
+    ///
+    /// An `init` field whose seeds reference a sibling `init` field comes
+    /// after it; otherwise declaration order is kept. Returns an empty list
+    /// when the account struct isn't found.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the seeds of the `init` fields reference each other in a cycle.
+    pub fn init_order(&self, program: &NormalizedProgram) -> Result<Vec<String>> {
+        let Some(account) = self
+            .account_struct_name
+            .as_deref()
+            .and_then(|name| program.find_account_struct(name))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let init_fields: Vec<_> = account
+            .fields
+            .iter()
+            .filter(|field| field.inferred_info.is_initialized)
+            .collect();
+
+        // For each init field, the sibling init fields its seeds reference
+        let mut pending: Vec<(&str, Vec<&str>)> = init_fields
+            .iter()
+            .map(|field| {
+                let seeds = field
+                    .find_constraint("seeds")
+                    .and_then(|c| c.value.as_deref())
+                    .map(parse_seeds)
+                    .unwrap_or_default();
+
+                let deps = init_fields
+                    .iter()
+                    .map(|other| other.name.as_str())
+                    .filter(|other| *other != field.name)
+                    .filter(|other| {
+                        seeds.iter().any(|seed| match seed {
+                            SeedElement::AccountRef(name) => name == other,
+                            SeedElement::Expr(e) => expr::contains_identifier(e, other),
+                            SeedElement::Literal(_) => false,
+                        })
+                    })
+                    .collect();
+
+                (field.name.as_str(), deps)
+            })
+            .collect();
+
+        // Kahn's algorithm, always taking the earliest declared ready field
+        let mut order = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let Some(idx) = pending.iter().position(|(_, deps)| deps.is_empty()) else {
+                let names: Vec<&str> = pending.iter().map(|(name, _)| *name).collect();
+                return Err(NormalizeError::Validation(format!(
+                    "Cyclic seed dependencies between init accounts in {}: {}",
+                    self.name,
+                    names.join(", ")
+                )));
+            };
+
+            let (name, _) = pending.remove(idx);
+            for (_, deps) in &mut pending {
+                deps.retain(|dep| *dep != name);
+            }
+            order.push(name.to_string());
+        }
+
+        Ok(order)
+    }
+
+    /// Names of the accounts that must sign this instruction
+    ///
+    /// Includes fields with an explicit or inferred `signer` constraint and
+    /// `Signer<'info>` fields, in declaration order. Returns an empty list
+    /// when the account struct isn't found.
+    pub fn required_signers(&self, program: &NormalizedProgram) -> Vec<String> {
+        self.account_struct_name
+            .as_deref()
+            .and_then(|name| program.find_account_struct(name))
+            .map(|account| {
+                account
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        field.inferred_info.requires_signer
+                            || FieldKind::from_field(field) == FieldKind::Signer
+                    })
+                    .map(|field| field.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether this instruction creates any accounts
+    ///
+    /// True if the body has an `Initialize` operation or the account struct
+    /// has an `init` or `init_if_needed` field.
+    pub fn initializes_accounts(&self, program: &NormalizedProgram) -> bool {
+        let initializes_in_body = matches!(
+            &self.body,
+            Some(InstructionBody::Basic(operations))
+                if operations.iter().any(|op| matches!(op, BasicOperation::Initialize { .. }))
+        );
+
+        initializes_in_body
+            || self
+                .account_struct_name
+                .as_deref()
+                .and_then(|name| program.find_account_struct(name))
+                .is_some_and(|account| {
+                    account.fields.iter().any(|field| {
+                        field.find_constraint("init").is_some()
+                            || field.find_constraint("init_if_needed").is_some()
+                    })
+                })
+    }
+
     /// Check if this is a Context parameter
     pub fn has_context_parameter(&self) -> bool {
         self.parameters.iter().any(|p| p.is_context)
@@ -120,15 +306,31 @@ impl NormalizedInstruction {
     pub fn get_context_parameter(&self) -> Option<&NormalizedParameter> {
         self.parameters.iter().find(|p| p.is_context)
     }
+
+    /// The `(name, type)` of each instruction data argument, in call order
+    ///
+    /// Excludes the context parameter, matching the arguments a CPI into
+    /// this instruction must serialize.
+    pub fn cpi_signature(&self) -> Vec<(String, String)> {
+        self.parameters
+            .iter()
+            .filter(|p| !p.is_context)
+            .map(|p| (p.name.clone(), p.ty.clone()))
+            .collect()
+    }
 }
 
 impl NormalizedParameter {
     /// Create a new parameter
     pub fn new(name: impl Into<String>, ty: impl Into<String>, is_context: bool) -> Self {
+        let ty = ty.into();
+        let is_optional = expr::split_generic_type(&ty).0 == "Option";
         Self {
             name: name.into(),
-            ty: ty.into(),
+            ty,
             is_context,
+            is_optional,
+            documentation: None,
         }
     }
 
@@ -139,6 +341,14 @@ impl NormalizedParameter {
             name: name.into(),
             ty: format!("Context<{}>", context_type),
             is_context: true,
+            is_optional: false,
+            documentation: None,
         }
     }
+
+    /// Set the documentation
+    pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
+        self.documentation = Some(docs.into());
+        self
+    }
 }
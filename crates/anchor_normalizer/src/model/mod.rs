@@ -4,6 +4,7 @@
 //! Anchor program, ready for IR generation.
 
 pub mod account;
+mod discriminator;
 pub mod instruction;
 pub mod program;
 pub mod validation;
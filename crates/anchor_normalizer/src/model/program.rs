@@ -2,16 +2,33 @@
 //!
 //! Defines the top-level normalized program structure
 
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use sha2::{Digest, Sha256};
 
+use crate::error::{NormalizeError, Result};
 use crate::model::{
-    account::{NormalizedAccountStruct, NormalizedRawAccount},
+    account::{NormalizedAccountField, NormalizedAccountStruct, NormalizedRawAccount, ProgramKind},
     instruction::NormalizedInstruction,
-    validation::ValidationIssue,
+    validation::{IssueCounts, ValidationIssue},
 };
+use crate::normalization::expr;
+
+/// Current version of the normalized program schema
+pub const SCHEMA_VERSION: &str = "2.0";
 
 /// Normalized representation of an Anchor program
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedProgram {
     /// Unique identifier for the program
     pub id: String,
@@ -28,6 +45,12 @@ pub struct NormalizedProgram {
     /// Raw account definitions
     pub raw_accounts: Vec<NormalizedRawAccount>,
 
+    /// Custom error enums (with #[error_code])
+    pub error_enums: Vec<NormalizedErrorEnum>,
+
+    /// Top-level items in source order, across the kinds above
+    pub item_order: Vec<NormalizedItemRef>,
+
     /// Program-level documentation extracted from comments
     pub documentation: Option<String>,
 
@@ -42,7 +65,9 @@ pub struct NormalizedProgram {
 }
 
 /// Normalized representation of a program module
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct NormalizedModule {
     /// Module name
     pub name: String,
@@ -57,8 +82,55 @@ pub struct NormalizedModule {
     pub documentation: Option<String>,
 }
 
+/// Normalized representation of a custom error enum
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct NormalizedErrorEnum {
+    /// Enum name
+    pub name: String,
+
+    /// Variant names in declaration order
+    pub variants: Vec<String>,
+
+    /// Message of each variant from `#[msg("...")]`, aligned with `variants`
+    pub messages: Vec<Option<String>>,
+}
+
+/// Kind of a top-level item recorded in [`NormalizedProgram::item_order`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub enum NormalizedItemKind {
+    /// A #[program] module
+    Module,
+
+    /// A #[derive(Accounts)] struct
+    AccountStruct,
+
+    /// A #[account] struct
+    RawAccount,
+
+    /// A #[error_code] enum
+    ErrorEnum,
+}
+
+/// Reference to a top-level item by kind and name
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct NormalizedItemRef {
+    /// Kind of item
+    pub kind: NormalizedItemKind,
+
+    /// Item name
+    pub name: String,
+}
+
 /// Source information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct SourceInfo {
     /// Source file path
     pub file_path: String,
@@ -76,10 +148,12 @@ impl NormalizedProgram {
             modules: Vec::new(),
             account_structs: Vec::new(),
             raw_accounts: Vec::new(),
+            error_enums: Vec::new(),
+            item_order: Vec::new(),
             documentation: None,
             validation_issues: Vec::new(),
             source_info: None,
-            schema_version: "1.0".to_string(),
+            schema_version: SCHEMA_VERSION.to_string(),
         }
     }
 
@@ -93,14 +167,297 @@ impl NormalizedProgram {
         self.raw_accounts.iter().find(|a| a.name == name)
     }
 
+    /// Find an error enum by name
+    pub fn find_error_enum(&self, name: &str) -> Option<&NormalizedErrorEnum> {
+        self.error_enums.iter().find(|e| e.name == name)
+    }
+
+    /// Resolve the raw account wrapped by an account field
+    ///
+    /// Looks up the data type of `Account<'info, T>` style fields among the
+    /// program's raw accounts.
+    pub fn resolve_field_account(
+        &self,
+        field: &NormalizedAccountField,
+    ) -> Option<&NormalizedRawAccount> {
+        let ty = field.wrapped_account_type()?;
+        self.find_raw_account(expr::last_segment(ty))
+    }
+
+    /// Get raw accounts that no account struct field wraps
+    ///
+    /// A raw account is referenced when some `Account<'info, T>` style field
+    /// resolves to it; the rest may be dead state definitions.
+    pub fn unreferenced_raw_accounts(&self) -> Vec<&NormalizedRawAccount> {
+        let referenced: BTreeSet<&str> = self
+            .account_structs
+            .iter()
+            .flat_map(|account| &account.fields)
+            .filter_map(|field| self.resolve_field_account(field))
+            .map(|raw| raw.name.as_str())
+            .collect();
+
+        self.raw_accounts
+            .iter()
+            .filter(|raw| !referenced.contains(raw.name.as_str()))
+            .collect()
+    }
+
+    /// Get the distinct external programs the program references
+    ///
+    /// Collected from `Program<'info, X>` and `Interface<'info, X>` fields,
+    /// which every CPI target must be passed as. Well-known programs use
+    /// their [`ProgramKind`] name (e.g., "System", "Token"). Sorted by name.
+    pub fn external_programs(&self) -> Vec<String> {
+        let programs: BTreeSet<String> = self
+            .account_structs
+            .iter()
+            .flat_map(|account| &account.fields)
+            .filter_map(|field| field.program_type())
+            .map(|ty| ProgramKind::from_type_name(ty).name().to_string())
+            .collect();
+
+        programs.into_iter().collect()
+    }
+
+    /// Get the distinct types referenced anywhere in the program
+    ///
+    /// Collected from instruction parameters, account struct fields and raw
+    /// account fields. Types are compacted to a canonical spelling, so
+    /// `Account<'info, Mint>` and `Account<'info,Mint>` are one entry.
+    pub fn referenced_types(&self) -> BTreeSet<String> {
+        let parameters = self
+            .modules
+            .iter()
+            .flat_map(|module| &module.instructions)
+            .flat_map(|instruction| &instruction.parameters)
+            .map(|parameter| &parameter.ty);
+        let fields = self
+            .account_structs
+            .iter()
+            .flat_map(|account| &account.fields)
+            .map(|field| &field.ty);
+        let raw_fields = self
+            .raw_accounts
+            .iter()
+            .flat_map(|account| &account.fields)
+            .map(|field| &field.ty);
+
+        parameters
+            .chain(fields)
+            .chain(raw_fields)
+            .map(|ty| expr::compact_expr(ty))
+            .collect()
+    }
+
+    /// Get every `Pubkey` field across raw accounts
+    ///
+    /// Returns `(account_name, field_name)` pairs in declaration order.
+    pub fn pubkey_fields(&self) -> Vec<(String, String)> {
+        self.raw_accounts
+            .iter()
+            .flat_map(|account| {
+                account
+                    .fields
+                    .iter()
+                    .filter(|field| expr::last_segment(&field.ty) == "Pubkey")
+                    .map(|field| (account.name.clone(), field.name.clone()))
+            })
+            .collect()
+    }
+
+    /// Compute a stable fingerprint of the program's semantic content
+    ///
+    /// Hashes names, instructions, accounts and constraints with SHA-256,
+    /// excluding the `id`, `source_info`, `validation_issues` and
+    /// `item_order` fields, so the same program parsed from different paths,
+    /// or with its items reordered, fingerprints equal.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the program can't be serialized for hashing.
+    #[cfg(feature = "serde")]
+    pub fn fingerprint(&self) -> Result<String> {
+        let mut value =
+            serde_json::to_value(self).map_err(|err| NormalizeError::Encode(err.to_string()))?;
+        if let Some(fields) = value.as_object_mut() {
+            for volatile in ["id", "source_info", "validation_issues", "item_order"] {
+                fields.remove(volatile);
+            }
+        }
+
+        // serde_json maps are sorted by key, so this encoding is canonical
+        let digest = Sha256::digest(value.to_string().as_bytes());
+        Ok(digest.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+
+    /// Encode the program in a compact binary form with bincode
+    ///
+    /// Much smaller and faster to read back than JSON or YAML, for caching
+    /// normalized programs between processes. Decode with
+    /// [`from_bincode`](Self::from_bincode).
+    ///
+    /// # Errors
+    ///
+    /// Fails if the program can't be encoded.
+    #[cfg(feature = "bincode")]
+    pub fn to_bincode(&self) -> Result<Vec<u8>> {
+        bincode::encode_to_vec(self, bincode::config::standard())
+            .map_err(|err| NormalizeError::Encode(err.to_string()))
+    }
+
+    /// Decode a program encoded with [`to_bincode`](Self::to_bincode)
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bytes are not a complete encoded program.
+    #[cfg(feature = "bincode")]
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self> {
+        let (program, read) = bincode::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(|err| NormalizeError::Decode(err.to_string()))?;
+        if read != bytes.len() {
+            return Err(NormalizeError::Decode(format!(
+                "{} trailing bytes after program",
+                bytes.len() - read
+            )));
+        }
+        Ok(program)
+    }
+
     /// Find an instruction by name (searches all modules)
     pub fn find_instruction(&self, name: &str) -> Option<&NormalizedInstruction> {
-        for module in &self.modules {
-            if let Some(instr) = module.instructions.iter().find(|i| i.name == name) {
-                return Some(instr);
+        self.all_instructions().find(|i| i.name == name)
+    }
+
+    /// Find an instruction by module-qualified name (e.g., "admin_program::initialize")
+    ///
+    /// Unlike [`find_instruction`](Self::find_instruction), this tells apart
+    /// instructions with the same name in different modules. Returns `None`
+    /// for a bare name.
+    pub fn find_instruction_qualified(&self, path: &str) -> Option<&NormalizedInstruction> {
+        let (module, name) = path.rsplit_once("::")?;
+        self.modules
+            .iter()
+            .find(|m| m.name == module)
+            .and_then(|m| m.find_instruction(name))
+    }
+
+    /// Iterate over the instructions of every module in declaration order
+    pub fn all_instructions(&self) -> impl Iterator<Item = &NormalizedInstruction> {
+        self.modules.iter().flat_map(|module| &module.instructions)
+    }
+
+    /// Map each instruction to the other instructions its body calls
+    ///
+    /// Only calls to functions named like an instruction of the program are
+    /// edges; helpers and self-calls are left out. Every instruction has an
+    /// entry, empty if it calls no other instruction.
+    #[cfg(feature = "std")]
+    pub fn instruction_call_graph(&self) -> std::collections::HashMap<String, Vec<String>> {
+        let names: BTreeSet<&str> = self.all_instructions().map(|i| i.name.as_str()).collect();
+
+        self.all_instructions()
+            .map(|instruction| {
+                let callees = instruction
+                    .calls
+                    .iter()
+                    .filter(|call| **call != instruction.name && names.contains(call.as_str()))
+                    .cloned()
+                    .collect();
+                (instruction.name.clone(), callees)
+            })
+            .collect()
+    }
+
+    /// Get all instructions matching a predicate
+    ///
+    /// For example, the instructions that initialize an account:
+    ///
+    /// ```
+    /// # use anchor_normalizer::model::{BasicOperation, InstructionBody, NormalizedProgram};
+    /// # let program = NormalizedProgram::new("id", "example");
+    /// let initializers = program.instructions_where(|instruction| {
+    ///     matches!(&instruction.body, Some(InstructionBody::Basic(ops))
+    ///         if ops.iter().any(|op| matches!(op, BasicOperation::Initialize { .. })))
+    /// });
+    /// # assert!(initializers.is_empty());
+    /// ```
+    pub fn instructions_where<F>(&self, f: F) -> Vec<&NormalizedInstruction>
+    where
+        F: Fn(&NormalizedInstruction) -> bool,
+    {
+        self.all_instructions()
+            .filter(|instruction| f(instruction))
+            .collect()
+    }
+
+    /// Sort modules, account structs, raw accounts and their fields by name
+    ///
+    /// Gives serialized output a stable order across refactors and merges.
+    /// Account struct field order is significant to Anchor, so this is for
+    /// output only; instructions keep their `declaration_index`.
+    pub fn sort_by_name(&mut self) {
+        self.modules.sort_by(|a, b| a.name.cmp(&b.name));
+        self.account_structs.sort_by(|a, b| a.name.cmp(&b.name));
+        for account in &mut self.account_structs {
+            account.fields.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        self.raw_accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        for account in &mut self.raw_accounts {
+            account.fields.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    /// Rename an account struct and every reference to it
+    ///
+    /// Updates the struct's name, each instruction's `account_struct_name`,
+    /// and whole-word occurrences in parameter and field types (e.g.,
+    /// `Context<Transfer>`).
+    ///
+    /// # Errors
+    ///
+    /// Fails if no account struct is named `old`, or if an account struct or
+    /// raw account named `new` already exists.
+    pub fn rename_account_struct(&mut self, old: &str, new: &str) -> Result<()> {
+        if self.find_account_struct(old).is_none() {
+            return Err(NormalizeError::MissingInfo(format!(
+                "No account struct named {}",
+                old
+            )));
+        }
+        if self.find_account_struct(new).is_some() || self.find_raw_account(new).is_some() {
+            return Err(NormalizeError::Validation(format!(
+                "Cannot rename {} to {}: name already exists",
+                old, new
+            )));
+        }
+
+        for account in &mut self.account_structs {
+            if account.name == old {
+                account.name = new.to_string();
+            }
+            for field in &mut account.fields {
+                field.ty = expr::replace_identifier(&field.ty, old, new);
+            }
+        }
+
+        for module in &mut self.modules {
+            for instruction in &mut module.instructions {
+                if instruction.account_struct_name.as_deref() == Some(old) {
+                    instruction.account_struct_name = Some(new.to_string());
+                }
+                for param in &mut instruction.parameters {
+                    param.ty = expr::replace_identifier(&param.ty, old, new);
+                }
             }
         }
-        None
+
+        Ok(())
+    }
+
+    /// Count the validation issues by severity
+    pub fn issue_counts(&self) -> IssueCounts {
+        IssueCounts::from_issues(&self.validation_issues)
     }
 
     /// Add a validation issue
@@ -128,6 +485,54 @@ impl NormalizedProgram {
     pub fn add_raw_account(&mut self, account: NormalizedRawAccount) {
         self.raw_accounts.push(account);
     }
+
+    /// Add an error enum to the program
+    pub fn add_error_enum(&mut self, error_enum: NormalizedErrorEnum) {
+        self.error_enums.push(error_enum);
+    }
+
+    /// Record a top-level item in source order
+    pub fn add_item_ref(&mut self, kind: NormalizedItemKind, name: impl Into<String>) {
+        self.item_order.push(NormalizedItemRef::new(kind, name));
+    }
+}
+
+impl NormalizedItemRef {
+    /// Create a new item reference
+    pub fn new(kind: NormalizedItemKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+        }
+    }
+}
+
+impl NormalizedErrorEnum {
+    /// Create a new error enum whose variants have no messages
+    pub fn new(name: impl Into<String>, variants: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            messages: vec![None; variants.len()],
+            variants,
+        }
+    }
+
+    /// Set the variant messages, aligned with the variants
+    pub fn with_messages(mut self, messages: Vec<Option<String>>) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Get the message of a variant, if it has one
+    pub fn message(&self, variant: &str) -> Option<&str> {
+        let index = self.variants.iter().position(|v| v == variant)?;
+        self.messages.get(index)?.as_deref()
+    }
+
+    /// Check whether the enum has a variant with the given name
+    pub fn has_variant(&self, name: &str) -> bool {
+        self.variants.iter().any(|v| v == name)
+    }
 }
 
 impl NormalizedModule {
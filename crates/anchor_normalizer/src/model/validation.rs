@@ -2,10 +2,15 @@
 //!
 //! Defines types for validation issues and related concerns
 
+use alloc::string::String;
+use core::fmt;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Validation issue found during normalization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct ValidationIssue {
     /// Severity level
     pub severity: IssueSeverity,
@@ -15,10 +20,37 @@ pub struct ValidationIssue {
 
     /// Related element
     pub element: String,
+
+    /// Stable identifier of the check that raised the issue (see [`RULES`])
+    pub rule_id: String,
 }
 
+/// Identifiers of the validation rules, as used in [`ValidationIssue::rule_id`]
+pub const RULES: &[&str] = &[
+    "duplicate-name",
+    "undefined-account",
+    "missing-type",
+    "visibility",
+    "empty-module",
+    "naming",
+    "unchecked-doc",
+    "payer",
+    "close-mut",
+    "shadowing",
+    "has-one",
+    "error-code",
+    "unknown-type",
+    "unused-struct",
+    "unknown-body",
+    "unconstrained",
+];
+
 /// Severity levels for validation issues
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub enum IssueSeverity {
     /// Informational message
     Info,
@@ -30,6 +62,50 @@ pub enum IssueSeverity {
     Error,
 }
 
+/// Number of validation issues at each severity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IssueCounts {
+    /// Error issues
+    pub errors: usize,
+
+    /// Warning issues
+    pub warnings: usize,
+
+    /// Info issues
+    pub info: usize,
+}
+
+impl IssueCounts {
+    /// Count a list of issues by severity
+    pub fn from_issues<'a>(issues: impl IntoIterator<Item = &'a ValidationIssue>) -> Self {
+        let mut counts = Self::default();
+        for issue in issues {
+            match issue.severity {
+                IssueSeverity::Error => counts.errors += 1,
+                IssueSeverity::Warning => counts.warnings += 1,
+                IssueSeverity::Info => counts.info += 1,
+            }
+        }
+        counts
+    }
+}
+
+impl fmt::Display for IssueCounts {
+    /// Formats as e.g. `3 errors, 1 warning, 2 info`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} error{}, {} warning{}, {} info",
+            self.errors,
+            plural(self.errors),
+            self.warnings,
+            plural(self.warnings),
+            self.info
+        )
+    }
+}
+
 impl ValidationIssue {
     /// Create a new validation issue
     pub fn new(
@@ -41,9 +117,16 @@ impl ValidationIssue {
             severity,
             message: message.into(),
             element: element.into(),
+            rule_id: String::new(),
         }
     }
 
+    /// Set the rule that raised the issue (builder pattern)
+    pub fn with_rule(mut self, rule_id: impl Into<String>) -> Self {
+        self.rule_id = rule_id.into();
+        self
+    }
+
     /// Create a new info issue
     pub fn info(message: impl Into<String>, element: impl Into<String>) -> Self {
         Self::new(IssueSeverity::Info, message, element)
@@ -7,9 +7,13 @@ use crate::model::account::{
     NormalizedAccountField, NormalizedAccountStruct, NormalizedConstraint, NormalizedRawAccount,
     NormalizedRawField,
 };
+use crate::model::instruction::NormalizedParameter;
+use crate::model::program::NormalizedErrorEnum;
+use crate::normalization::expr;
 use anchor_parser::model::account::{
     Account, AccountField, Constraint, RawAccount, RawAccountField,
 };
+use anchor_parser::model::ErrorEnum;
 
 /// Normalize an Anchor account struct
 ///
@@ -31,12 +35,25 @@ pub fn normalize_account_struct(account: &Account) -> Result<NormalizedAccountSt
         normalized.add_field(normalize_account_field(field)?);
     }
 
+    // Carry over the #[instruction(...)] arguments
+    for arg in &account.instruction_args {
+        normalized.add_instruction_arg(NormalizedParameter::new(
+            arg.name.clone(),
+            arg.ty.clone(),
+            false,
+        ));
+    }
+    normalized.where_clause = account.where_clause.clone();
+
     Ok(normalized)
 }
 
 /// Normalize an account field
 fn normalize_account_field(field: &AccountField) -> Result<NormalizedAccountField> {
     let mut normalized = NormalizedAccountField::new(field.name.clone(), field.ty.clone());
+    if let Some(docs) = &field.documentation {
+        normalized = normalized.with_documentation(docs);
+    }
 
     // Normalize constraints
     for constraint in &field.constraints {
@@ -47,18 +64,46 @@ fn normalize_account_field(field: &AccountField) -> Result<NormalizedAccountFiel
 }
 
 /// Normalize a constraint
+///
+/// Namespaced constraint names are compacted (e.g., `associated_token :: mint`
+/// becomes `associated_token::mint`). A trailing `@ Enum::Variant` is split
+/// off the value, or off the name for constraints without a value (e.g.,
+/// `mut @ VaultError::NotMutable`), into the error code.
 fn normalize_constraint(constraint: &Constraint) -> Result<NormalizedConstraint> {
-    Ok(NormalizedConstraint::new(
-        constraint.constraint_type.clone(),
-        constraint.value.clone(),
+    let (constraint_type, type_error) = expr::split_error_code(&constraint.constraint_type);
+    let (value, value_error) = match constraint.value.as_deref().map(expr::split_error_code) {
+        Some((value, error_code)) => (Some(value), error_code),
+        None => (None, None),
+    };
+
+    let mut normalized = NormalizedConstraint::new(
+        expr::compact_expr(constraint_type),
+        value,
         false, // Not inferred
-    ))
+    );
+    if let Some(error_code) = value_error.or(type_error) {
+        normalized = normalized.with_error_code(expr::compact_expr(error_code));
+    }
+
+    Ok(normalized)
+}
+
+/// Normalize a custom error enum
+pub fn normalize_error_enum(error_enum: &ErrorEnum) -> Result<NormalizedErrorEnum> {
+    Ok(
+        NormalizedErrorEnum::new(error_enum.name.clone(), error_enum.variants.clone())
+            .with_messages(error_enum.messages.clone()),
+    )
 }
 
 /// Normalize a raw account
 pub fn normalize_raw_account(account: &RawAccount) -> Result<NormalizedRawAccount> {
     let mut normalized =
-        NormalizedRawAccount::new(account.name.clone(), account.visibility.clone()); // Normalize fields
+        NormalizedRawAccount::new(account.name.clone(), account.visibility.clone());
+    normalized.derives_init_space = account.derives_init_space;
+    normalized.discriminator_override = account.discriminator_override.clone();
+
+    // Normalize fields
     for field in &account.fields {
         normalized.add_field(normalize_raw_field(field)?);
     }
@@ -68,9 +113,12 @@ pub fn normalize_raw_account(account: &RawAccount) -> Result<NormalizedRawAccoun
 
 /// Normalize a raw account field
 fn normalize_raw_field(field: &RawAccountField) -> Result<NormalizedRawField> {
-    Ok(NormalizedRawField::new(
+    let mut normalized = NormalizedRawField::new(
         field.name.clone(),
         field.ty.clone(),
         field.visibility.clone(),
-    ))
+    );
+    normalized.max_len = field.max_len.clone();
+
+    Ok(normalized)
 }
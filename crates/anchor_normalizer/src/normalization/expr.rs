@@ -0,0 +1,273 @@
+//! Helpers for inspecting type and expression strings
+//!
+//! The parser stores types and constraint values as strings, so the
+//! normalizer uses these small utilities to look inside them.
+
+use alloc::{string::String, vec::Vec};
+
+/// Split an expression on a separator, ignoring separators nested in brackets
+///
+/// Separators inside string literals and turbofish generics (`size_of::<T>()`)
+/// are ignored too. A bare `<` is a comparison, so `a < b, c` splits at the
+/// comma.
+pub fn split_top_level(s: &str, separator: char) -> Vec<&str> {
+    split_top_level_in(s, separator, false)
+}
+
+/// Split a type on a separator, ignoring separators nested in brackets or generics
+///
+/// `HashMap<K, V>, u8` splits into `HashMap<K, V>` and `u8`.
+pub fn split_top_level_type(s: &str, separator: char) -> Vec<&str> {
+    split_top_level_in(s, separator, true)
+}
+
+/// Split on top-level separators, treating every `<` as a generic opener in
+/// type context and only `::<` otherwise
+fn split_top_level_in(s: &str, separator: char, type_context: bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut generic_depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut prev = '\0';
+    let mut start = 0;
+
+    for (idx, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            prev = c;
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '<' if type_context || s[..idx].ends_with("::") => generic_depth += 1,
+            // `->` and `=>` are arrows, not closing generics
+            '>' if generic_depth > 0 && prev != '-' && prev != '=' => generic_depth -= 1,
+            c if c == separator && depth == 0 && generic_depth == 0 => {
+                parts.push(s[start..idx].trim());
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    parts.push(s[start..].trim());
+
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// Split a trailing custom error off a constraint (`x > 0 @ VaultError::TooSmall`)
+///
+/// The `@` must sit outside brackets and string literals and be followed by
+/// a path; otherwise the whole string is returned with no error.
+pub fn split_error_code(s: &str) -> (&str, Option<&str>) {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut at = None;
+
+    for (idx, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '@' if depth == 0 => at = Some(idx),
+            _ => {}
+        }
+    }
+
+    let Some(idx) = at else {
+        return (s.trim(), None);
+    };
+    let error_code = s[idx + 1..].trim();
+    let is_path = !error_code.is_empty()
+        && error_code
+            .chars()
+            .all(|c| is_ident_char(c) || c == ':' || c.is_whitespace());
+    if is_path {
+        (s[..idx].trim(), Some(error_code))
+    } else {
+        (s.trim(), None)
+    }
+}
+
+/// Split a type into its outer name (last path segment) and generic arguments
+///
+/// `Account<'info, Vault>` yields `("Account", ["'info", "Vault"])`.
+pub fn split_generic_type(ty: &str) -> (&str, Vec<&str>) {
+    let ty = ty.trim();
+
+    let (path, args) = match ty.find('<') {
+        Some(start) if ty.ends_with('>') => (
+            &ty[..start],
+            split_top_level_type(&ty[start + 1..ty.len() - 1], ','),
+        ),
+        _ => (ty, Vec::new()),
+    };
+
+    (last_segment(path), args)
+}
+
+/// Get the last segment of a path (e.g., `Vault` for `crate::state::Vault`)
+pub fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// Extract the account data type wrapped by an account field type
+///
+/// Handles `Account<'info, T>`, `AccountLoader<'info, T>` and
+/// `InterfaceAccount<'info, T>`, including when boxed, returning `T` as
+/// written.
+pub fn wrapped_account_type(ty: &str) -> Option<&str> {
+    let (name, args) = split_generic_type(ty);
+
+    match name {
+        "Box" => args.first().and_then(|inner| wrapped_account_type(inner)),
+        "Account" | "AccountLoader" | "InterfaceAccount" => {
+            args.last().copied().filter(|arg| !arg.starts_with('\''))
+        }
+        _ => None,
+    }
+}
+
+/// Compact a token-stream expression string (e.g., `authority . key ()`)
+///
+/// Removes whitespace except between two identifier characters, leaving
+/// string and byte-string literals untouched.
+pub fn compact_expr(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    let mut pending_space = false;
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+
+        if pending_space && is_ident_char(c) && out.chars().last().is_some_and(is_ident_char) {
+            out.push(' ');
+        }
+        pending_space = false;
+
+        if c == '"' {
+            in_string = true;
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Replace whole-word occurrences of an identifier
+///
+/// `Context<Transfer>` becomes `Context<Send>` when renaming `Transfer`,
+/// while `TransferChecked` is left alone.
+pub fn replace_identifier(s: &str, from: &str, to: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(idx) = rest.find(from) {
+        let end = idx + from.len();
+        let before_ok = !rest[..idx].chars().last().is_some_and(is_ident_char);
+        let after_ok = !rest[end..].chars().next().is_some_and(is_ident_char);
+
+        out.push_str(&rest[..idx]);
+        out.push_str(if before_ok && after_ok { to } else { from });
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+
+    out
+}
+
+/// Get the free identifiers of an expression, in order
+///
+/// Skips identifiers reached through `.` or `::` (only `state` is free in
+/// `state.amount`, and only `Vault` in `Vault::INIT_SPACE`), as well as
+/// numbers and the contents of string literals.
+pub fn free_identifiers(s: &str) -> Vec<&str> {
+    let mut idents = Vec::new();
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (idx, c) in s.char_indices().chain(core::iter::once((s.len(), ' '))) {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        if is_ident_char(c) {
+            start.get_or_insert(idx);
+            continue;
+        }
+
+        if let Some(begin) = start.take() {
+            let ident = &s[begin..idx];
+            let prefix = s[..begin].trim_end();
+            let qualified = prefix.ends_with('.') || prefix.ends_with("::");
+            // A `b` directly before a quote is a byte-string prefix
+            if is_identifier(ident) && !qualified && !(ident == "b" && c == '"') {
+                idents.push(ident);
+            }
+        }
+        in_string = c == '"';
+    }
+
+    idents
+}
+
+/// Check whether an identifier occurs as a whole word
+pub fn contains_identifier(s: &str, ident: &str) -> bool {
+    s.match_indices(ident).any(|(idx, _)| {
+        let end = idx + ident.len();
+        !s[..idx].chars().last().is_some_and(is_ident_char)
+            && !s[end..].chars().next().is_some_and(is_ident_char)
+    })
+}
+
+/// Check whether a character can appear in an identifier or number
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Check whether a string is a plain identifier (e.g., `authority`)
+pub fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_') && chars.all(is_ident_char)
+}
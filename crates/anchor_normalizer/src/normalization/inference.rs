@@ -1,14 +1,146 @@
 // In normalization/inference.rs
+use crate::config::NormalizeConfig;
 use crate::error::Result;
 use crate::model::{
     instruction::{BasicOperation, InstructionBody},
-    NormalizedAccountStruct, NormalizedConstraint, NormalizedInstruction, NormalizedProgram,
+    BumpSource, FieldKind, NormalizedAccountStruct, NormalizedConstraint, NormalizedInstruction,
+    NormalizedProgram, PdaInfo, ProgramKind,
 };
+use crate::normalization::expr;
+use crate::normalization::seeds::parse_seeds;
+use crate::normalization::space::resolve_space;
+
+/// A pluggable inference pass over a normalized program
+///
+/// Implement this to add custom inferences and run them with
+/// [`run_inference`] alongside (or instead of) [`default_rules`].
+pub trait InferenceRule {
+    /// Apply the rule, updating the program in place
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()>;
+}
+
+/// Infers basic operations for instructions from their accounts
+pub struct InstructionOperationsRule;
+
+/// Infers constraints that aren't explicitly specified
+///
+/// `Signer` fields named in `authority_field_names` get an inferred
+/// signer constraint.
+pub struct FieldConstraintsRule {
+    pub authority_field_names: Vec<String>,
+}
+
+impl Default for FieldConstraintsRule {
+    fn default() -> Self {
+        Self {
+            authority_field_names: NormalizeConfig::default().authority_field_names,
+        }
+    }
+}
+
+/// Infers relationships between fields from has_one/belongs_to
+pub struct AccountRelationshipsRule;
+
+/// Classifies `Program<'info, X>` fields
+pub struct ProgramKindsRule;
+
+/// Parses PDA seeds into structured elements
+pub struct PdaSeedsRule;
+
+/// Evaluates `space` constraints
+pub struct SpaceRule;
+
+/// Classifies each field's account type
+pub struct FieldKindsRule;
+
+impl InferenceRule for InstructionOperationsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_instruction_operations(program)
+    }
+}
+
+impl InferenceRule for FieldConstraintsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_field_constraints(program, &self.authority_field_names)
+    }
+}
+
+impl InferenceRule for AccountRelationshipsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_account_relationships(program)
+    }
+}
+
+impl InferenceRule for ProgramKindsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_program_kinds(program)
+    }
+}
+
+impl InferenceRule for PdaSeedsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_pdas(program)
+    }
+}
+
+impl InferenceRule for SpaceRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_space(program)
+    }
+}
+
+impl InferenceRule for FieldKindsRule {
+    fn apply(&self, program: &mut NormalizedProgram) -> Result<()> {
+        infer_field_kinds(program)
+    }
+}
+
+/// The built-in inference rules, in the order they are applied
+pub fn default_rules() -> Vec<Box<dyn InferenceRule>> {
+    config_rules(&NormalizeConfig::default())
+}
+
+/// The built-in inference rules, tuned by a normalization configuration
+pub fn config_rules(config: &NormalizeConfig) -> Vec<Box<dyn InferenceRule>> {
+    vec![
+        Box::new(InstructionOperationsRule),
+        Box::new(FieldConstraintsRule {
+            authority_field_names: config.authority_field_names.clone(),
+        }),
+        Box::new(AccountRelationshipsRule),
+        Box::new(ProgramKindsRule),
+        Box::new(PdaSeedsRule),
+        Box::new(SpaceRule),
+        Box::new(FieldKindsRule),
+    ]
+}
+
+/// Run inference rules over a program in order
+///
+/// # Arguments
+///
+/// * `program` - The normalized program to enhance
+/// * `rules` - The rules to apply
+///
+/// # Returns
+///
+/// Success or the first error returned by a rule
+pub fn run_inference(
+    program: &mut NormalizedProgram,
+    rules: &[Box<dyn InferenceRule>],
+) -> Result<()> {
+    for rule in rules {
+        rule.apply(program)?;
+    }
+
+    Ok(())
+}
 
 /// Infer missing semantic information in the normalized program
 ///
 /// This function adds semantics that aren't explicitly present in the
 /// parsed AST but can be inferred from known patterns and conventions.
+/// It runs the [`default_rules`].
 ///
 /// # Arguments
 ///
@@ -18,16 +150,7 @@ use crate::model::{
 ///
 /// Success or an error if inference fails
 pub fn infer_missing_semantics(program: &mut NormalizedProgram) -> Result<()> {
-    // Infer operation semantics for instructions
-    infer_instruction_operations(program)?;
-
-    // Infer field constraints not explicitly provided
-    infer_field_constraints(program)?;
-
-    // Infer relationships between accounts
-    infer_account_relationships(program)?;
-
-    Ok(())
+    run_inference(program, &default_rules())
 }
 
 /// Infer basic operations for instructions based on their name and accounts
@@ -94,6 +217,16 @@ fn infer_operations_from_account(
         }
     }
 
+    // Check for realloc operations, paid for by realloc::payer
+    for field in &account.fields {
+        if field.find_constraint("realloc").is_some() {
+            operations.push(BasicOperation::Realloc {
+                target: field.name.clone(),
+                payer: field.inferred_info.realloc_payer.clone(),
+            });
+        }
+    }
+
     // Add more operations based on instruction name
     match instruction.name.as_str() {
         "initialize" | "init" | "create" => {
@@ -136,16 +269,30 @@ fn infer_operations_from_account(
 }
 
 /// Infer constraints that aren't explicitly specified
-fn infer_field_constraints(program: &mut NormalizedProgram) -> Result<()> {
+fn infer_field_constraints(
+    program: &mut NormalizedProgram,
+    authority_field_names: &[String],
+) -> Result<()> {
     // Collect the constraints to add
     let mut constraints_to_add = Vec::new();
 
     for account_idx in 0..program.account_structs.len() {
-        for field_idx in 0..program.account_structs[account_idx].fields.len() {
-            let field = &program.account_structs[account_idx].fields[field_idx];
+        let account = &program.account_structs[account_idx];
+
+        // Fields that pay for another field's initialization
+        let payers: Vec<String> = account
+            .fields
+            .iter()
+            .filter_map(|f| f.find_constraint("payer"))
+            .filter_map(|c| c.value.as_deref())
+            .map(expr::compact_expr)
+            .collect();
+
+        for field_idx in 0..account.fields.len() {
+            let field = &account.fields[field_idx];
 
-            // Infer signer constraint for fields named "authority"
-            if (field.name == "authority" || field.name == "owner" || field.name == "admin")
+            // Infer signer constraint for authority fields
+            if authority_field_names.contains(&field.name)
                 && !field
                     .constraints
                     .iter()
@@ -159,11 +306,14 @@ fn infer_field_constraints(program: &mut NormalizedProgram) -> Result<()> {
                 ));
             }
 
-            // Infer mut constraint for fields that have init
-            if field
-                .constraints
-                .iter()
-                .any(|c| c.constraint_type == "init")
+            // Infer mut constraint for fields that have init or pay for one,
+            // since both have their lamports changed
+            let is_payer = payers.contains(&field.name);
+            if (is_payer
+                || field
+                    .constraints
+                    .iter()
+                    .any(|c| c.constraint_type == "init"))
                 && !field.constraints.iter().any(|c| c.constraint_type == "mut")
             {
                 constraints_to_add.push((
@@ -230,3 +380,128 @@ fn infer_account_relationships(program: &mut NormalizedProgram) -> Result<()> {
 
     Ok(())
 }
+
+/// Infer which program a `Program<'info, X>` field refers to
+///
+/// Token accounts held through `InterfaceAccount` belong to
+/// [`ProgramKind::TokenInterface`].
+fn infer_program_kinds(program: &mut NormalizedProgram) -> Result<()> {
+    for account in &mut program.account_structs {
+        for field in &mut account.fields {
+            if let Some(program_type) = field.program_type() {
+                field.inferred_info.program_kind = Some(ProgramKind::from_type_name(program_type));
+            } else if field.is_token_interface_account() {
+                field.inferred_info.program_kind = Some(ProgramKind::TokenInterface);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Infer PDA information from `seeds` constraints
+fn infer_pdas(program: &mut NormalizedProgram) -> Result<()> {
+    let mut pdas = Vec::new();
+
+    for (account_idx, account) in program.account_structs.iter().enumerate() {
+        for (field_idx, field) in account.fields.iter().enumerate() {
+            let seeds = field
+                .find_constraint("seeds")
+                .and_then(|c| c.value.as_deref())
+                .map(parse_seeds);
+
+            if let Some(seeds) = seeds {
+                let bump_source = match field
+                    .find_constraint("bump")
+                    .and_then(|c| c.value.as_deref())
+                {
+                    Some(value) => BumpSource::Stored(expr::compact_expr(value)),
+                    None => BumpSource::Canonical,
+                };
+
+                let stored_bump_field = match &bump_source {
+                    BumpSource::Stored(expr) => resolve_stored_bump(program, account, expr),
+                    BumpSource::Canonical => None,
+                };
+
+                let seeds_program = field
+                    .find_constraint("seeds::program")
+                    .and_then(|c| c.value.as_deref())
+                    .map(expr::compact_expr);
+
+                pdas.push((
+                    account_idx,
+                    field_idx,
+                    PdaInfo {
+                        seeds,
+                        bump_source,
+                        program: seeds_program,
+                        stored_bump_field,
+                    },
+                ));
+            }
+        }
+    }
+
+    for (account_idx, field_idx, pda) in pdas {
+        program.account_structs[account_idx].fields[field_idx]
+            .inferred_info
+            .pda = Some(pda);
+    }
+
+    Ok(())
+}
+
+/// Resolve a stored bump like `vault.bump` to the raw account field it reads
+///
+/// The expression must be `field.member`, where `field` wraps a raw
+/// account of the program that has a `member` field.
+fn resolve_stored_bump(
+    program: &NormalizedProgram,
+    account: &NormalizedAccountStruct,
+    expr: &str,
+) -> Option<(String, String)> {
+    let (base, member) = expr.split_once('.')?;
+    let raw = program.resolve_field_account(account.find_field(base)?)?;
+    raw.fields
+        .iter()
+        .find(|f| f.name == member)
+        .map(|f| (raw.name.clone(), f.name.clone()))
+}
+
+/// Classify every account field
+fn infer_field_kinds(program: &mut NormalizedProgram) -> Result<()> {
+    for account in &mut program.account_structs {
+        for field in &mut account.fields {
+            field.inferred_info.kind = FieldKind::from_field(field);
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluate `space` constraints that only use known sizes
+fn infer_space(program: &mut NormalizedProgram) -> Result<()> {
+    let mut resolved = Vec::new();
+
+    for (account_idx, account) in program.account_structs.iter().enumerate() {
+        for (field_idx, field) in account.fields.iter().enumerate() {
+            let space = field
+                .find_constraint("space")
+                .and_then(|c| c.value.as_deref())
+                .and_then(|value| resolve_space(value, program));
+
+            if let Some(space) = space {
+                resolved.push((account_idx, field_idx, space));
+            }
+        }
+    }
+
+    for (account_idx, field_idx, space) in resolved {
+        program.account_structs[account_idx].fields[field_idx]
+            .inferred_info
+            .resolved_space = Some(space);
+    }
+
+    Ok(())
+}
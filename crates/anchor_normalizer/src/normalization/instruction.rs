@@ -31,6 +31,13 @@ pub fn normalize_instruction(instruction: &Instruction) -> Result<NormalizedInst
         normalized = normalized.with_account_struct(ctx_type);
     }
 
+    // Interface instructions carry #[interface] on the function
+    normalized = normalized.with_interface(instruction.has_attribute("interface"));
+
+    // Sysvars found in the body are runtime dependencies
+    normalized.uses_sysvars = instruction.sysvars.clone();
+    normalized.calls = instruction.calls.clone();
+
     // Normalize parameters
     for param in &instruction.parameters {
         normalized.add_parameter(normalize_parameter(param)?);
@@ -44,11 +51,12 @@ pub fn normalize_instruction(instruction: &Instruction) -> Result<NormalizedInst
 
 /// Normalize an instruction parameter
 fn normalize_parameter(param: &Parameter) -> Result<NormalizedParameter> {
-    Ok(NormalizedParameter::new(
-        param.name.clone(),
-        param.ty.clone(),
-        param.is_context,
-    ))
+    let mut normalized =
+        NormalizedParameter::new(param.name.clone(), param.ty.clone(), param.is_context);
+    if let Some(docs) = &param.documentation {
+        normalized = normalized.with_documentation(docs);
+    }
+    Ok(normalized)
 }
 
 /// Extract context type from a parameter type string
@@ -3,11 +3,24 @@
 //! This module contains the logic for transforming parsed AST into
 //! a semantically rich normalized model.
 
+#[cfg(feature = "std")]
 pub mod account;
+pub mod expr;
+#[cfg(feature = "std")]
 pub mod inference;
+#[cfg(feature = "std")]
 pub mod instruction;
+#[cfg(feature = "std")]
 pub mod program;
+#[cfg(feature = "std")]
+mod reverse;
+pub mod seeds;
+pub mod space;
+#[cfg(feature = "std")]
 pub mod validation;
 
 // Re-export the main normalization function
-pub use program::normalize_program;
+#[cfg(feature = "std")]
+pub use program::{
+    normalize_each_program_with_config, normalize_program, normalize_program_with_config,
+};
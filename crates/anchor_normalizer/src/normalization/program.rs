@@ -2,15 +2,18 @@
 //!
 //! Handles normalization of the top-level Program structure
 
+use crate::config::NormalizeConfig;
 use crate::error::{NormalizeError, Result};
-use crate::model::{NormalizedModule, NormalizedProgram, SourceInfo};
+use crate::model::{NormalizedItemKind, NormalizedModule, NormalizedProgram, SourceInfo};
 use crate::normalization::{
-    account::{normalize_account_struct, normalize_raw_account},
-    inference::infer_missing_semantics,
+    account::{normalize_account_struct, normalize_error_enum, normalize_raw_account},
+    expr,
+    inference::{config_rules, run_inference},
     instruction::normalize_instruction,
     validation::validate_program,
 };
-use anchor_parser::model::{Program, ProgramModule};
+use anchor_parser::model::{ItemKind, Program, ProgramModule};
+use std::collections::HashSet;
 
 /// Normalize an Anchor program into a semantically rich model
 ///
@@ -25,8 +28,25 @@ use anchor_parser::model::{Program, ProgramModule};
 ///
 /// A normalized program model or an error if normalization fails
 pub fn normalize_program(program: &Program) -> Result<NormalizedProgram> {
+    normalize_program_with_config(program, &NormalizeConfig::default())
+}
+
+/// Normalize an Anchor program using the given configuration
+///
+/// # Arguments
+///
+/// * `program` - The parsed Anchor program
+/// * `config` - Normalization options
+///
+/// # Returns
+///
+/// A normalized program model or an error if normalization fails
+pub fn normalize_program_with_config(
+    program: &Program,
+    config: &NormalizeConfig,
+) -> Result<NormalizedProgram> {
     // Extract program name
-    let name = extract_program_name(program)?;
+    let name = extract_program_name(program, config)?;
 
     // Generate a program ID
     let id = generate_program_id(program);
@@ -44,6 +64,16 @@ pub fn normalize_program(program: &Program) -> Result<NormalizedProgram> {
         normalized.add_module(normalize_module(module)?);
     }
 
+    // Number instructions in declaration order across all modules
+    for (index, instruction) in normalized
+        .modules
+        .iter_mut()
+        .flat_map(|module| &mut module.instructions)
+        .enumerate()
+    {
+        instruction.declaration_index = index;
+    }
+
     // Normalize account structs
     for account in &program.account_structs {
         normalized.add_account_struct(normalize_account_struct(account)?);
@@ -54,18 +84,123 @@ pub fn normalize_program(program: &Program) -> Result<NormalizedProgram> {
         normalized.add_raw_account(normalize_raw_account(account)?);
     }
 
+    // Normalize error enums
+    for error_enum in &program.error_enums {
+        normalized.add_error_enum(normalize_error_enum(error_enum)?);
+    }
+
+    // Keep the source order of the top-level items
+    for item in &program.item_order {
+        normalized.add_item_ref(normalize_item_kind(item.kind), item.name.clone());
+    }
+
     // Establish relationships between instructions and account structs
     link_instructions_to_accounts(&mut normalized)?;
 
     // Infer missing semantic information
-    infer_missing_semantics(&mut normalized)?;
+    run_inference(&mut normalized, &config_rules(config))?;
 
     // Validate the normalized program
-    validate_program(&mut normalized)?;
+    validate_program(&mut normalized, config)?;
 
     Ok(normalized)
 }
 
+/// Normalize each `#[program]` module as a separate program
+///
+/// Every module becomes its own [`NormalizedProgram`] named after it,
+/// carrying only the account structs its instructions use and the raw
+/// accounts those structs wrap. Error enums, imports and file attributes
+/// are shared by all of them. When the source path is known, the module
+/// name is appended to the program ID so the IDs stay distinct.
+///
+/// # Arguments
+///
+/// * `program` - The parsed Anchor program
+/// * `config` - Normalization options
+///
+/// # Returns
+///
+/// One normalized program per module, in declaration order
+pub fn normalize_each_program_with_config(
+    program: &Program,
+    config: &NormalizeConfig,
+) -> Result<Vec<NormalizedProgram>> {
+    program
+        .program_modules
+        .iter()
+        .map(|module| {
+            let mut normalized =
+                normalize_program_with_config(&module_program(program, module), config)?;
+            if program.source_path.is_some() {
+                normalized.id = format!("{}::{}", normalized.id, module.name);
+            }
+            Ok(normalized)
+        })
+        .collect()
+}
+
+/// Build the program holding one module and the items it references
+fn module_program(program: &Program, module: &ProgramModule) -> Program {
+    let account_names: HashSet<&str> = module
+        .instructions
+        .iter()
+        .filter_map(|instruction| instruction.context_type.as_deref())
+        .collect();
+    let account_structs: Vec<_> = program
+        .account_structs
+        .iter()
+        .filter(|account| account_names.contains(account.name.as_str()))
+        .cloned()
+        .collect();
+
+    let raw_names: HashSet<&str> = account_structs
+        .iter()
+        .flat_map(|account| &account.fields)
+        .filter_map(|field| expr::wrapped_account_type(&field.ty))
+        .map(expr::last_segment)
+        .collect();
+    let raw_accounts: Vec<_> = program
+        .raw_accounts
+        .iter()
+        .filter(|account| raw_names.contains(account.name.as_str()))
+        .cloned()
+        .collect();
+
+    let item_order = program
+        .item_order
+        .iter()
+        .filter(|item| match item.kind {
+            ItemKind::ProgramModule => item.name == module.name,
+            ItemKind::AccountStruct => account_names.contains(item.name.as_str()),
+            ItemKind::RawAccount => raw_names.contains(item.name.as_str()),
+            ItemKind::ErrorEnum => true,
+        })
+        .cloned()
+        .collect();
+
+    Program {
+        program_modules: vec![module.clone()],
+        account_structs,
+        raw_accounts,
+        item_order,
+        imports: program.imports.clone(),
+        error_enums: program.error_enums.clone(),
+        file_attributes: program.file_attributes.clone(),
+        source_path: program.source_path.clone(),
+    }
+}
+
+/// Map a parsed item kind to its normalized equivalent
+fn normalize_item_kind(kind: ItemKind) -> NormalizedItemKind {
+    match kind {
+        ItemKind::ProgramModule => NormalizedItemKind::Module,
+        ItemKind::AccountStruct => NormalizedItemKind::AccountStruct,
+        ItemKind::RawAccount => NormalizedItemKind::RawAccount,
+        ItemKind::ErrorEnum => NormalizedItemKind::ErrorEnum,
+    }
+}
+
 /// Normalize a program module
 fn normalize_module(module: &ProgramModule) -> Result<NormalizedModule> {
     let mut normalized = NormalizedModule::new(module.name.clone(), module.visibility.clone());
@@ -78,17 +213,64 @@ fn normalize_module(module: &ProgramModule) -> Result<NormalizedModule> {
     Ok(normalized)
 }
 
-/// Extract the program name from the Program model
-fn extract_program_name(program: &Program) -> Result<String> {
-    // If there's only one program module, use its name
-    if program.program_modules.len() == 1 {
-        return Ok(program.program_modules[0].name.clone());
+/// Pick the program module that is the program's entrypoint
+///
+/// With several `#[program]` modules in one file, prefers the module named
+/// after the crate: the source file stem, or for a `src/lib.rs` (or
+/// `src/main.rs`) the crate directory, with `-` read as `_`. Falls back to
+/// the first module.
+///
+/// # Arguments
+///
+/// * `program` - The parsed Anchor program
+///
+/// # Returns
+///
+/// The primary module, or `None` if the program has no modules
+pub fn primary_module(program: &Program) -> Option<&ProgramModule> {
+    let candidates = program
+        .source_path
+        .as_deref()
+        .map(crate_name_candidates)
+        .unwrap_or_default();
+
+    candidates
+        .iter()
+        .find_map(|name| program.find_program_module(name))
+        .or_else(|| program.program_modules.first())
+}
+
+/// Names a program's crate may go by, judging from its source path
+fn crate_name_candidates(source_path: &str) -> Vec<String> {
+    let path = std::path::Path::new(source_path);
+    let mut candidates = Vec::new();
+
+    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+        if stem == "lib" || stem == "main" {
+            // programs/<crate>/src/lib.rs
+            let crate_dir = path
+                .parent()
+                .filter(|dir| dir.file_name().is_some_and(|name| name == "src"))
+                .and_then(|src| src.parent())
+                .and_then(|dir| dir.file_name())
+                .and_then(|name| name.to_str());
+            candidates.extend(crate_dir.map(String::from));
+        } else {
+            candidates.push(stem.to_string());
+        }
     }
 
-    // If there are multiple program modules, use the first one
-    // but add a validation warning
-    if !program.program_modules.is_empty() {
-        return Ok(program.program_modules[0].name.clone());
+    candidates
+        .iter()
+        .map(|name| name.replace('-', "_"))
+        .collect()
+}
+
+/// Extract the program name from the Program model
+fn extract_program_name(program: &Program, config: &NormalizeConfig) -> Result<String> {
+    // Use the entrypoint module's name, when there are modules
+    if let Some(module) = primary_module(program) {
+        return Ok(module.name.clone());
     }
 
     // If there are no program modules, try to infer from source path
@@ -102,6 +284,11 @@ fn extract_program_name(program: &Program) -> Result<String> {
         }
     }
 
+    // Fall back to the caller-supplied name, if any
+    if let Some(name) = &config.default_program_name {
+        return Ok(name.clone());
+    }
+
     // If we can't determine a name, return an error
     Err(NormalizeError::MissingInfo(
         "Could not determine program name".to_string(),
@@ -115,9 +302,9 @@ fn generate_program_id(program: &Program) -> String {
         return format!("program:{}", source_path);
     }
 
-    // Otherwise use the first module name
-    if !program.program_modules.is_empty() {
-        return format!("program:{}", program.program_modules[0].name);
+    // Otherwise use the primary module name
+    if let Some(module) = primary_module(program) {
+        return format!("program:{}", module.name);
     }
 
     // Fallback to a timestamp-based ID
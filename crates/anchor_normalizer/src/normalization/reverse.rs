@@ -0,0 +1,177 @@
+//! Conversion from the normalized model back to the parser model
+//!
+//! Rebuilds a parser-side [`Program`] from a [`NormalizedProgram`] so the
+//! normalization pipeline can be re-run, e.g. to check it is idempotent.
+//! Inferred information (inferred constraints, field info, instruction
+//! bodies, validation issues) is dropped and recomputed on normalization.
+
+use crate::model::{
+    NormalizedAccountField, NormalizedAccountStruct, NormalizedConstraint, NormalizedErrorEnum,
+    NormalizedInstruction, NormalizedItemKind, NormalizedModule, NormalizedProgram,
+    NormalizedRawAccount,
+};
+use anchor_parser::model::{
+    Account, AccountField, Constraint, ErrorEnum, Instruction, ItemKind, Parameter, Program,
+    ProgramModule, RawAccount, RawAccountField,
+};
+
+impl From<&NormalizedProgram> for Program {
+    fn from(normalized: &NormalizedProgram) -> Self {
+        let mut program = Program::new();
+        program.source_path = normalized
+            .source_info
+            .as_ref()
+            .map(|info| info.file_path.clone());
+
+        for module in &normalized.modules {
+            program.add_program_module(module.into());
+        }
+        for account in &normalized.account_structs {
+            program.add_account_struct(account.into());
+        }
+        for account in &normalized.raw_accounts {
+            program.add_raw_account(account.into());
+        }
+        for error_enum in &normalized.error_enums {
+            program.add_error_enum(error_enum.into());
+        }
+        for item in &normalized.item_order {
+            let kind = match item.kind {
+                NormalizedItemKind::Module => ItemKind::ProgramModule,
+                NormalizedItemKind::AccountStruct => ItemKind::AccountStruct,
+                NormalizedItemKind::RawAccount => ItemKind::RawAccount,
+                NormalizedItemKind::ErrorEnum => ItemKind::ErrorEnum,
+            };
+            program.add_item_ref(kind, item.name.clone());
+        }
+
+        program
+    }
+}
+
+impl From<&NormalizedModule> for ProgramModule {
+    fn from(module: &NormalizedModule) -> Self {
+        let mut converted = ProgramModule::new(module.name.clone(), module.visibility.clone());
+        for instruction in &module.instructions {
+            converted.add_instruction(instruction.into());
+        }
+        converted
+    }
+}
+
+impl From<&NormalizedInstruction> for Instruction {
+    fn from(instruction: &NormalizedInstruction) -> Self {
+        let mut converted =
+            Instruction::new(instruction.name.clone(), instruction.visibility.clone());
+
+        if let Some(ret_type) = &instruction.return_type {
+            converted.set_return_type(ret_type);
+        }
+        if let Some(account_struct) = &instruction.account_struct_name {
+            converted.set_context_type(account_struct);
+        }
+        if instruction.is_interface {
+            converted.add_attribute("interface");
+        }
+        for sysvar in &instruction.uses_sysvars {
+            converted.add_sysvar(sysvar);
+        }
+        for call in &instruction.calls {
+            converted.add_call(call);
+        }
+        for param in &instruction.parameters {
+            let mut converted_param =
+                Parameter::new(param.name.clone(), param.ty.clone(), param.is_context);
+            if let Some(docs) = &param.documentation {
+                converted_param = converted_param.with_documentation(docs);
+            }
+            converted.add_parameter(converted_param);
+        }
+
+        converted
+    }
+}
+
+impl From<&NormalizedAccountStruct> for Account {
+    fn from(account: &NormalizedAccountStruct) -> Self {
+        let mut converted = Account::new(account.name.clone(), account.visibility.clone());
+        for field in &account.fields {
+            converted.add_field(field.into());
+        }
+        for arg in &account.instruction_args {
+            converted.add_instruction_arg(Parameter::new(arg.name.clone(), arg.ty.clone(), false));
+        }
+        converted.where_clause = account.where_clause.clone();
+        converted
+    }
+}
+
+impl From<&NormalizedAccountField> for AccountField {
+    fn from(field: &NormalizedAccountField) -> Self {
+        let mut converted = AccountField::new(field.name.clone(), field.ty.clone());
+        if let Some(docs) = &field.documentation {
+            converted = converted.with_documentation(docs);
+        }
+
+        // Inferred constraints are re-derived when normalizing again
+        for constraint in field.constraints.iter().filter(|c| !c.is_inferred) {
+            converted.add_constraint(constraint.into());
+        }
+
+        converted
+    }
+}
+
+impl From<&NormalizedRawAccount> for RawAccount {
+    fn from(account: &NormalizedRawAccount) -> Self {
+        let mut converted = RawAccount::new(account.name.clone(), account.visibility.clone());
+        converted.derives_init_space = account.derives_init_space;
+        converted.discriminator_override = account.discriminator_override.clone();
+
+        for field in &account.fields {
+            let mut raw_field = RawAccountField::new(
+                field.name.clone(),
+                field.ty.clone(),
+                field.visibility.clone(),
+            );
+            if let Some(max_len) = field.max_len_attribute() {
+                raw_field.add_attribute(max_len);
+                raw_field = raw_field.with_max_len(field.max_len.clone());
+            }
+            converted.add_field(raw_field);
+        }
+
+        converted
+    }
+}
+
+impl From<&NormalizedConstraint> for Constraint {
+    /// Re-attaches the error code the way it is written in source: after
+    /// the value, or after the name when there is no value
+    fn from(constraint: &NormalizedConstraint) -> Self {
+        let with_error = |text: &str| match &constraint.error_code {
+            Some(error_code) => format!("{} @ {}", text, error_code),
+            None => text.to_string(),
+        };
+
+        match &constraint.value {
+            Some(value) => {
+                Constraint::with_value(constraint.constraint_type.clone(), with_error(value))
+            }
+            None => Constraint::without_value(with_error(&constraint.constraint_type)),
+        }
+    }
+}
+
+impl From<&NormalizedErrorEnum> for ErrorEnum {
+    fn from(error_enum: &NormalizedErrorEnum) -> Self {
+        let mut converted = ErrorEnum::new(error_enum.name.clone());
+        for variant in &error_enum.variants {
+            match error_enum.message(variant) {
+                Some(message) => converted.add_variant_with_message(variant.clone(), message),
+                None => converted.add_variant(variant.clone()),
+            }
+        }
+        converted
+    }
+}
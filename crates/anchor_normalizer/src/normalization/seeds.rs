@@ -0,0 +1,91 @@
+//! PDA seed analysis
+//!
+//! Parses `seeds = [...]` constraint values into structured elements so
+//! tooling can reproduce program derived addresses.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::model::SeedElement;
+use crate::normalization::expr;
+
+/// Parse a seeds constraint value into structured seed elements
+///
+/// Byte-string literals (`b"vault"`) and string literals turned into bytes
+/// (`"vault".as_bytes()`) decode to [`SeedElement::Literal`], account keys
+/// (`authority.key().as_ref()`) become [`SeedElement::AccountRef`], and
+/// anything else is kept as a compacted [`SeedElement::Expr`].
+///
+/// # Arguments
+///
+/// * `value` - The seeds constraint value (e.g., `[b"vault", authority.key().as_ref()]`)
+pub fn parse_seeds(value: &str) -> Vec<SeedElement> {
+    let value = expr::compact_expr(value);
+    let inner = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .unwrap_or(&value);
+
+    expr::split_top_level(inner, ',')
+        .into_iter()
+        .map(parse_seed_element)
+        .collect()
+}
+
+/// Classify a single compacted seed element
+fn parse_seed_element(element: &str) -> SeedElement {
+    let base = element
+        .strip_suffix(".as_ref()")
+        .or_else(|| element.strip_suffix(".as_bytes()"))
+        .unwrap_or(element);
+
+    if let Some(bytes) = decode_string_literal(base) {
+        return SeedElement::Literal(bytes);
+    }
+
+    if let Some(account) = base.strip_suffix(".key()") {
+        if expr::is_identifier(account) {
+            return SeedElement::AccountRef(account.to_string());
+        }
+    }
+
+    SeedElement::Expr(element.to_string())
+}
+
+/// Decode a `b"..."` or `"..."` literal into its bytes
+fn decode_string_literal(literal: &str) -> Option<Vec<u8>> {
+    let body = literal
+        .strip_prefix("b\"")
+        .or_else(|| literal.strip_prefix('"'))?
+        .strip_suffix('"')?;
+
+    let mut bytes = Vec::with_capacity(body.len());
+    let mut chars = body.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            '0' => bytes.push(0),
+            '\\' => bytes.push(b'\\'),
+            '\'' => bytes.push(b'\''),
+            '"' => bytes.push(b'"'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(bytes)
+}
@@ -0,0 +1,119 @@
+//! Account space resolution
+//!
+//! Computes Anchor `InitSpace` sizes and evaluates `space = ...`
+//! constraint expressions where every term is known.
+
+use alloc::string::ToString;
+
+use crate::model::NormalizedProgram;
+use crate::normalization::expr;
+
+/// Compute the `InitSpace` size of a type
+///
+/// Follows Anchor's `InitSpace` rules: `String` and `Vec<T>` take a 4-byte
+/// length prefix plus `max_len` elements, `Option<T>` adds a 1-byte tag, and
+/// arrays multiply the element size. Each `String`/`Vec` level consumes the
+/// next length, so `#[max_len(10, 32)]` sizes a `Vec<String>` as 10 strings
+/// of 32 bytes. Returns `None` for types whose size is unknown (e.g.,
+/// user-defined structs or a missing `max_len`).
+///
+/// # Arguments
+///
+/// * `ty` - The field type
+/// * `max_len` - The field's `#[max_len(N, ...)]` lengths, outermost first
+pub fn type_size(ty: &str, max_len: &[usize]) -> Option<usize> {
+    let ty = ty.trim();
+
+    // Fixed-size arrays: [T; N]
+    if let Some(inner) = ty.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let parts = expr::split_top_level_type(inner, ';');
+        if let [element, len] = parts.as_slice() {
+            let len: usize = len.trim_end_matches("usize").parse().ok()?;
+            return Some(len * type_size(element, max_len)?);
+        }
+        return None;
+    }
+
+    let (name, args) = expr::split_generic_type(ty);
+    match (name, args.as_slice()) {
+        ("bool" | "u8" | "i8", _) => Some(1),
+        ("u16" | "i16", _) => Some(2),
+        ("u32" | "i32" | "f32", _) => Some(4),
+        ("u64" | "i64" | "f64", _) => Some(8),
+        ("u128" | "i128", _) => Some(16),
+        ("Pubkey", _) => Some(32),
+        ("String", _) => Some(4 + max_len.first()?),
+        ("Vec", [element]) => {
+            let (len, rest) = max_len.split_first()?;
+            Some(4 + len * type_size(element, rest)?)
+        }
+        ("Option", [inner]) => Some(1 + type_size(inner, max_len)?),
+        ("Box", [inner]) => type_size(inner, max_len),
+        _ => None,
+    }
+}
+
+/// Evaluate a `space = ...` constraint expression
+///
+/// Supports sums and products of integer literals, `T::INIT_SPACE`
+/// references to program accounts that derive `InitSpace`
+/// (e.g., `8 + Vault::INIT_SPACE`), and `size_of::<T>()` calls, which
+/// resolve to the byte sum of the account's field layout.
+///
+/// # Arguments
+///
+/// * `space` - The constraint value
+/// * `program` - The program used to resolve `INIT_SPACE` references
+pub fn resolve_space(space: &str, program: &NormalizedProgram) -> Option<usize> {
+    let space = expr::compact_expr(space);
+
+    expr::split_top_level(&space, '+')
+        .into_iter()
+        .map(|term| {
+            expr::split_top_level(term, '*')
+                .into_iter()
+                .map(|factor| resolve_factor(factor, program))
+                .try_fold(1usize, |acc, factor| acc.checked_mul(factor?))
+        })
+        .try_fold(0usize, |acc, term| acc.checked_add(term?))
+}
+
+/// Resolve a single factor of a space expression
+fn resolve_factor(factor: &str, program: &NormalizedProgram) -> Option<usize> {
+    let factor = factor
+        .strip_prefix('(')
+        .and_then(|f| f.strip_suffix(')'))
+        .map_or(factor.to_string(), |inner| inner.to_string());
+
+    if let Some(account) = factor.strip_suffix("::INIT_SPACE") {
+        return program
+            .find_raw_account(expr::last_segment(account))?
+            .init_space();
+    }
+
+    if let Some(account) = size_of_type(&factor) {
+        return program
+            .find_raw_account(expr::last_segment(account))?
+            .layout_size();
+    }
+
+    if factor.contains('+') || factor.contains('*') {
+        return resolve_space(&factor, program);
+    }
+
+    factor
+        .trim_end_matches("usize")
+        .replace('_', "")
+        .parse()
+        .ok()
+}
+
+/// Extract `T` from a `size_of::<T>()` call (with any path prefix)
+fn size_of_type(factor: &str) -> Option<&str> {
+    let call = factor.strip_suffix("()")?;
+    let (path, ty) = call.split_once("::<")?;
+    if expr::last_segment(path) != "size_of" {
+        return None;
+    }
+    ty.strip_suffix('>')
+}
@@ -1,34 +1,136 @@
 // In normalization/validation.rs
+use crate::config::NormalizeConfig;
 use crate::error::Result;
-use crate::model::{validation::ValidationIssue, NormalizedProgram};
+use crate::model::{validation::ValidationIssue, FieldKind, InstructionBody, NormalizedProgram};
+use crate::normalization::expr;
 use std::collections::HashSet;
 
+/// Instruction names that collide with Anchor-generated items
+const RESERVED_INSTRUCTION_NAMES: &[&str] = &["idl", "new", "state", "fallback", "global"];
+
+/// A pluggable validation pass over a normalized program
+///
+/// Implement this to add project-specific checks and run them with
+/// [`validate_with`] alongside (or instead of) [`default_rules`]. Plain
+/// functions and closures with the same signature are rules too.
+pub trait ValidationRule {
+    /// Check the program, pushing any issues found
+    fn validate(&self, program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>);
+}
+
+impl<F> ValidationRule for F
+where
+    F: Fn(&NormalizedProgram, &mut Vec<ValidationIssue>),
+{
+    fn validate(&self, program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+        self(program, issues)
+    }
+}
+
+/// The built-in validation rules, in the order they are applied
+pub fn default_rules() -> Vec<Box<dyn ValidationRule>> {
+    config_rules(&NormalizeConfig::default())
+}
+
+/// The built-in validation rules, tuned by a normalization configuration
+///
+/// Adds the known account types to the type reference check, and the
+/// unknown-body report when `warn_unknown_bodies` is set.
+pub fn config_rules(config: &NormalizeConfig) -> Vec<Box<dyn ValidationRule>> {
+    let type_config = config.clone();
+    let mut rules: Vec<Box<dyn ValidationRule>> = vec![
+        // Check for unique account struct names
+        Box::new(validate_unique_account_names),
+        // Check for unique field names within each account
+        Box::new(validate_unique_field_names),
+        // Validate instruction references to account structs
+        Box::new(validate_instruction_references),
+        // Validate field types
+        Box::new(validate_field_types),
+        // Check for consistent visibility
+        Box::new(validate_visibility),
+        // Check for program modules without instructions
+        Box::new(validate_module_instructions),
+        // Check for instruction names reserved by Anchor
+        Box::new(validate_reserved_names),
+        // Check has_one targets against the referenced account data
+        Box::new(validate_has_one_targets),
+        // Check that constraint error codes name parsed error variants
+        Box::new(validate_constraint_error_codes),
+        // Check that unchecked accounts explain why they are safe
+        Box::new(validate_unchecked_docs),
+        // Check that payers name fields of the same struct
+        Box::new(validate_payer_fields),
+        // Check that closed accounts are mutable
+        Box::new(validate_close_targets),
+        // Check that instruction arguments don't shadow account fields
+        Box::new(validate_instruction_arg_shadowing),
+        // Check that wrapped account types are defined or known
+        Box::new(
+            move |program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>| {
+                validate_account_type_references(program, &type_config, issues)
+            },
+        ),
+        // Report raw accounts that no field wraps
+        Box::new(validate_raw_account_references),
+        // Report fields without constraints that may have been forgotten
+        Box::new(validate_unconstrained_fields),
+    ];
+
+    // Optionally report instructions the tool couldn't understand
+    if config.warn_unknown_bodies {
+        rules.push(Box::new(validate_instruction_bodies));
+    }
+
+    rules
+}
+
 /// Validate a normalized program
 ///
-/// Checks the program structure for consistency and completeness.
+/// Checks the program structure for consistency and completeness with the
+/// built-in [`config_rules`].
 ///
 /// # Arguments
 ///
 /// * `program` - The normalized program to validate
+/// * `config` - Normalization options (e.g., extra known account types)
 ///
 /// # Returns
 ///
 /// Success or an error if validation fails
-pub fn validate_program(program: &mut NormalizedProgram) -> Result<()> {
+pub fn validate_program(program: &mut NormalizedProgram, config: &NormalizeConfig) -> Result<()> {
+    validate_with(program, &config_rules(config))
+}
+
+/// Run validation rules over a program
+///
+/// Issues from every rule are sorted deterministically, most severe first
+/// and then by element, and added to the program.
+///
+/// # Arguments
+///
+/// * `program` - The normalized program to validate
+/// * `rules` - The rules to apply
+///
+/// # Returns
+///
+/// Success or an error if validation fails
+pub fn validate_with(
+    program: &mut NormalizedProgram,
+    rules: &[Box<dyn ValidationRule>],
+) -> Result<()> {
     // Collect validation issues in a Vec
     let mut issues = Vec::new();
+    for rule in rules {
+        rule.validate(program, &mut issues);
+    }
 
-    // Check for unique account struct names
-    validate_unique_account_names(program, &mut issues);
-
-    // Validate instruction references to account structs
-    validate_instruction_references(program, &mut issues);
-
-    // Validate field types
-    validate_field_types(program, &mut issues);
-
-    // Check for consistent visibility
-    validate_visibility(program, &mut issues);
+    // Sort deterministically: most severe first, then by element
+    issues.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.element.cmp(&b.element))
+    });
 
     // Add all collected issues to the program
     for issue in issues {
@@ -45,20 +147,65 @@ fn validate_unique_account_names(program: &NormalizedProgram, issues: &mut Vec<V
     // Check account structs
     for account in &program.account_structs {
         if !names.insert(&account.name) {
-            issues.push(ValidationIssue::error(
-                format!("Duplicate account struct name: {}", account.name),
-                account.name.clone(),
-            ));
+            issues.push(
+                ValidationIssue::error(
+                    format!("Duplicate account struct name: {}", account.name),
+                    account.name.clone(),
+                )
+                .with_rule("duplicate-name"),
+            );
         }
     }
 
     // Check raw accounts
     for account in &program.raw_accounts {
         if !names.insert(&account.name) {
-            issues.push(ValidationIssue::error(
-                format!("Duplicate account name: {}", account.name),
-                account.name.clone(),
-            ));
+            issues.push(
+                ValidationIssue::error(
+                    format!("Duplicate account name: {}", account.name),
+                    account.name.clone(),
+                )
+                .with_rule("duplicate-name"),
+            );
+        }
+    }
+}
+
+/// Validate that field names are unique within each account struct and raw account
+fn validate_unique_field_names(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        let mut names = HashSet::new();
+        for field in &account.fields {
+            if !names.insert(&field.name) {
+                issues.push(
+                    ValidationIssue::error(
+                        format!(
+                            "Duplicate field name {} in account struct {}",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("duplicate-name"),
+                );
+            }
+        }
+    }
+
+    for account in &program.raw_accounts {
+        let mut names = HashSet::new();
+        for field in &account.fields {
+            if !names.insert(&field.name) {
+                issues.push(
+                    ValidationIssue::error(
+                        format!(
+                            "Duplicate field name {} in raw account {}",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("duplicate-name"),
+                );
+            }
         }
     }
 }
@@ -75,22 +222,28 @@ fn validate_instruction_references(program: &NormalizedProgram, issues: &mut Vec
         for instruction in &module.instructions {
             if let Some(account_name) = &instruction.account_struct_name {
                 if !account_names.contains(account_name) {
-                    issues.push(ValidationIssue::warning(
+                    issues.push(
+                        ValidationIssue::warning(
+                            format!(
+                                "Instruction {} references undefined account struct {}",
+                                instruction.name, account_name
+                            ),
+                            instruction.name.clone(),
+                        )
+                        .with_rule("undefined-account"),
+                    );
+                }
+            } else if instruction.has_context_parameter() {
+                issues.push(
+                    ValidationIssue::warning(
                         format!(
-                            "Instruction {} references undefined account struct {}",
-                            instruction.name, account_name
+                            "Instruction {} has Context parameter but no associated account struct",
+                            instruction.name
                         ),
                         instruction.name.clone(),
-                    ));
-                }
-            } else if instruction.has_context_parameter() {
-                issues.push(ValidationIssue::warning(
-                    format!(
-                        "Instruction {} has Context parameter but no associated account struct",
-                        instruction.name
-                    ),
-                    instruction.name.clone(),
-                ));
+                    )
+                    .with_rule("undefined-account"),
+                );
             }
         }
     }
@@ -102,13 +255,16 @@ fn validate_field_types(program: &NormalizedProgram, issues: &mut Vec<Validation
     for account in &program.account_structs {
         for field in &account.fields {
             if field.ty.is_empty() {
-                issues.push(ValidationIssue::warning(
-                    format!(
-                        "Field {} in account {} has no type information",
-                        field.name, account.name
-                    ),
-                    format!("{}.{}", account.name, field.name),
-                ));
+                issues.push(
+                    ValidationIssue::warning(
+                        format!(
+                            "Field {} in account {} has no type information",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("missing-type"),
+                );
             }
         }
     }
@@ -117,13 +273,16 @@ fn validate_field_types(program: &NormalizedProgram, issues: &mut Vec<Validation
     for account in &program.raw_accounts {
         for field in &account.fields {
             if field.ty.is_empty() {
-                issues.push(ValidationIssue::warning(
-                    format!(
-                        "Field {} in raw account {} has no type information",
-                        field.name, account.name
-                    ),
-                    format!("{}.{}", account.name, field.name),
-                ));
+                issues.push(
+                    ValidationIssue::warning(
+                        format!(
+                            "Field {} in raw account {} has no type information",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("missing-type"),
+                );
             }
         }
     }
@@ -135,14 +294,343 @@ fn validate_visibility(program: &NormalizedProgram, issues: &mut Vec<ValidationI
     for module in &program.modules {
         for instruction in &module.instructions {
             if instruction.visibility != "pub" {
-                issues.push(ValidationIssue::info(
+                issues.push(
+                    ValidationIssue::info(
+                        format!(
+                            "Instruction {} has non-public visibility: {}",
+                            instruction.name, instruction.visibility
+                        ),
+                        instruction.name.clone(),
+                    )
+                    .with_rule("visibility"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that program modules define at least one instruction
+fn validate_module_instructions(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for module in &program.modules {
+        if module.instructions.is_empty() {
+            issues.push(
+                ValidationIssue::info(
+                    format!("Program module {} has no instructions", module.name),
+                    module.name.clone(),
+                )
+                .with_rule("empty-module"),
+            );
+        }
+    }
+}
+
+/// Validate that instructions don't use names reserved by Anchor
+fn validate_reserved_names(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for module in &program.modules {
+        for instruction in &module.instructions {
+            if RESERVED_INSTRUCTION_NAMES.contains(&instruction.name.as_str()) {
+                issues.push(
+                    ValidationIssue::warning(
+                        format!(
+                            "Instruction {} uses a name reserved by Anchor",
+                            instruction.name
+                        ),
+                        instruction.name.clone(),
+                    )
+                    .with_rule("naming"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that unchecked account fields carry a `/// CHECK:` doc comment
+///
+/// Anchor refuses to build `UncheckedAccount` and `AccountInfo` fields
+/// without one.
+fn validate_unchecked_docs(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            if FieldKind::from_field(field) != FieldKind::Unchecked {
+                continue;
+            }
+
+            let has_check = field
+                .documentation
+                .as_deref()
+                .is_some_and(|docs| docs.lines().any(|line| line.starts_with("CHECK")));
+            if !has_check {
+                issues.push(
+                    ValidationIssue::warning(
+                        format!(
+                            "Unchecked field {} in account {} has no /// CHECK: doc comment",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("unchecked-doc"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that every `payer = X` names a field in the same account struct
+///
+/// Also requires a `realloc::payer` on every `realloc` field.
+fn validate_payer_fields(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            if field.find_constraint("realloc").is_some()
+                && field.inferred_info.realloc_payer.is_none()
+            {
+                issues.push(
+                    ValidationIssue::error(
+                        format!(
+                            "Field {} in account {} is reallocated without a realloc::payer",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("payer"),
+                );
+            }
+
+            let Some(payer) = field
+                .find_constraint("payer")
+                .and_then(|c| c.value.as_deref())
+            else {
+                continue;
+            };
+
+            let payer = expr::compact_expr(payer);
+            if account.find_field(&payer).is_none() {
+                issues.push(
+                    ValidationIssue::error(
+                        format!(
+                            "payer {} of field {} in account {} is not a field of {}",
+                            payer, field.name, account.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("payer"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that fields with a `close` constraint are `mut`
+fn validate_close_targets(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            if field.find_constraint("close").is_some() && !field.inferred_info.requires_mut {
+                issues.push(
+                    ValidationIssue::error(
+                        format!(
+                            "Field {} in account {} is closed but not mut",
+                            field.name, account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    )
+                    .with_rule("close-mut"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that `#[instruction(...)]` arguments don't share a name with a field
+///
+/// Constraint expressions naming such an argument are ambiguous.
+fn validate_instruction_arg_shadowing(
+    program: &NormalizedProgram,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for account in &program.account_structs {
+        for arg in &account.instruction_args {
+            if account.find_field(&arg.name).is_some() {
+                issues.push(
+                    ValidationIssue::warning(
+                        format!(
+                            "Instruction argument {} shadows field {} in account {}",
+                            arg.name, arg.name, account.name
+                        ),
+                        format!("{}.{}", account.name, arg.name),
+                    )
+                    .with_rule("shadowing"),
+                );
+            }
+        }
+    }
+}
+
+/// Validate that has_one targets exist on the referenced account and are Pubkey fields
+fn validate_has_one_targets(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            let Some(raw_account) = program.resolve_field_account(field) else {
+                continue;
+            };
+
+            for constraint in &field.constraints {
+                if constraint.constraint_type != "has_one" {
+                    continue;
+                }
+                let Some(target) = &constraint.value else {
+                    continue;
+                };
+
+                match raw_account.find_field(target) {
+                    Some(raw_field) if raw_field.ty == "Pubkey" => {}
+                    Some(raw_field) => issues.push(ValidationIssue::warning(
+                        format!(
+                            "has_one target {} on field {} in account {} has type {} in {}, expected Pubkey",
+                            target, field.name, account.name, raw_field.ty, raw_account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    ).with_rule("has-one")),
+                    // A missing target can never match, so Anchor would reject every call
+                    None => issues.push(ValidationIssue::error(
+                        format!(
+                            "has_one target {} on field {} in account {} is not a field of {}",
+                            target, field.name, account.name, raw_account.name
+                        ),
+                        format!("{}.{}", account.name, field.name),
+                    ).with_rule("has-one")),
+                }
+            }
+        }
+    }
+}
+
+/// Validate that `@ Enum::Variant` error codes on constraints exist
+///
+/// A variant missing from a parsed error enum is an error. Enums that
+/// aren't defined in the program, such as Anchor's built-in `ErrorCode`
+/// or ones from another crate, are reported for information only.
+fn validate_constraint_error_codes(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            for constraint in &field.constraints {
+                let Some(error_code) = &constraint.error_code else {
+                    continue;
+                };
+                let Some((enum_path, variant)) = error_code.rsplit_once("::") else {
+                    continue;
+                };
+                let element = format!("{}.{}", account.name, field.name);
+
+                match program.find_error_enum(expr::last_segment(enum_path)) {
+                    Some(error_enum) if error_enum.has_variant(variant) => {}
+                    Some(error_enum) => issues.push(
+                        ValidationIssue::error(
+                            format!(
+                                "Constraint {} on field {} in account {} maps to {}, which is not a variant of {}",
+                                constraint.constraint_type, field.name, account.name, error_code, error_enum.name
+                            ),
+                            element,
+                        )
+                        .with_rule("error-code"),
+                    ),
+                    None => issues.push(
+                        ValidationIssue::info(
+                            format!(
+                                "Constraint {} on field {} in account {} maps to {}, whose error enum is not defined in this program",
+                                constraint.constraint_type, field.name, account.name, error_code
+                            ),
+                            element,
+                        )
+                        .with_rule("error-code"),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// Validate that account data types wrapped by fields are defined or known
+fn validate_account_type_references(
+    program: &NormalizedProgram,
+    config: &NormalizeConfig,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    for account in &program.account_structs {
+        for field in &account.fields {
+            if let Some(ty) = field.wrapped_account_type() {
+                let type_name = expr::last_segment(ty);
+                if program.find_raw_account(type_name).is_none() && !config.is_known_type(type_name)
+                {
+                    issues.push(
+                        ValidationIssue::warning(
+                            format!(
+                                "Field {} in account {} references unknown account type {}",
+                                field.name, account.name, type_name
+                            ),
+                            format!("{}.{}", account.name, field.name),
+                        )
+                        .with_rule("unknown-type"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Report raw accounts that are never wrapped by any account struct field
+fn validate_raw_account_references(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for raw in program.unreferenced_raw_accounts() {
+        issues.push(
+            ValidationIssue::info(
+                format!(
+                    "Raw account {} is not referenced by any account struct field",
+                    raw.name
+                ),
+                raw.name.clone(),
+            )
+            .with_rule("unused-struct"),
+        );
+    }
+}
+
+/// Report account struct fields without any explicit constraint
+///
+/// Program fields (`Program<'info, X>`, `Interface<'info, X>`) need none,
+/// as their type alone checks the program ID, so they aren't reported.
+fn validate_unconstrained_fields(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for account in &program.account_structs {
+        for field in account.unconstrained_fields() {
+            if field.program_type().is_some() {
+                continue;
+            }
+            issues.push(
+                ValidationIssue::info(
                     format!(
-                        "Instruction {} has non-public visibility: {}",
-                        instruction.name, instruction.visibility
+                        "Field {} in account {} has no constraints",
+                        field.name, account.name
+                    ),
+                    format!("{}.{}", account.name, field.name),
+                )
+                .with_rule("unconstrained"),
+            );
+        }
+    }
+}
+
+/// Report instructions whose body is still unknown after inference
+fn validate_instruction_bodies(program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+    for instruction in program.all_instructions() {
+        if matches!(instruction.body, None | Some(InstructionBody::Unknown)) {
+            issues.push(
+                ValidationIssue::info(
+                    format!(
+                        "Instruction {} has a body that could not be analyzed",
+                        instruction.name
                     ),
                     instruction.name.clone(),
-                ));
-            }
+                )
+                .with_rule("unknown-body"),
+            );
         }
     }
 }
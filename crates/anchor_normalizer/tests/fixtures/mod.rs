@@ -1,10 +1,22 @@
 //! Test fixtures for normalization tests
 
+use std::path::PathBuf;
+
 use anchor_parser::model::{
     Account, AccountField, Constraint, Instruction, Parameter, Program, ProgramModule, RawAccount,
     RawAccountField,
 };
 
+/// Parse one of the example programs in the repository's examples directory
+pub fn example_program(name: &str) -> Program {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../examples");
+    path.push(name);
+    path.push("lib.rs");
+
+    anchor_parser::parse_file(&path).expect("Example program should parse")
+}
+
 /// Create a simple hello world program fixture
 pub fn hello_world_program() -> Program {
     let mut program = Program::new();
@@ -142,6 +154,47 @@ pub fn token_program() -> Program {
     program
 }
 
+/// Create a vault program fixture with a `has_one = authority` constraint
+///
+/// # Arguments
+///
+/// * `vault_fields` - Name and type of each field on the `Vault` raw account
+pub fn vault_program(vault_fields: &[(&str, &str)]) -> Program {
+    let mut program = Program::new();
+
+    // Create the program module with a withdraw instruction
+    let mut module = ProgramModule::new("vault_program", "pub");
+    let instruction = Instruction::new("withdraw", "pub")
+        .with_parameter(Parameter::new_context("ctx", "Withdraw"))
+        .with_return_type("Result<()>")
+        .with_context_type("Withdraw");
+    module.add_instruction(instruction);
+    program.add_program_module(module);
+
+    // Create Withdraw account struct
+    let mut withdraw_account = Account::new("Withdraw", "pub");
+
+    let mut vault_field = AccountField::new("vault", "Account<'info, Vault>");
+    vault_field.add_constraint(Constraint::without_value("mut"));
+    vault_field.add_constraint(Constraint::with_value("has_one", "authority"));
+    withdraw_account.add_field(vault_field);
+
+    let mut authority_field = AccountField::new("authority", "Signer<'info>");
+    authority_field.add_constraint(Constraint::without_value("signer"));
+    withdraw_account.add_field(authority_field);
+
+    program.add_account_struct(withdraw_account);
+
+    // Create Vault raw account
+    let mut vault = RawAccount::new("Vault", "pub");
+    for (name, ty) in vault_fields {
+        vault.add_field(RawAccountField::new(*name, *ty, "pub"));
+    }
+    program.add_raw_account(vault);
+
+    program
+}
+
 /// Creates a program with various validation issues for testing error handling
 ///
 /// # Arguments
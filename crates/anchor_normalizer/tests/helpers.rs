@@ -67,7 +67,7 @@ where
         // Check if the body contains basic operations
         match body {
             anchor_normalizer::InstructionBody::Basic(ops) => {
-                assert!(ops.iter().any(|op| predicate(op)), "{}", error_msg);
+                assert!(ops.iter().any(predicate), "{}", error_msg);
             }
             _ => panic!("Expected basic operations for instruction"),
         }
@@ -0,0 +1,61 @@
+//! Model construction without the `std` feature
+//!
+//! Run with `cargo test -p anchor_normalizer --no-default-features` to check
+//! the model builds as `no_std` + `alloc`.
+
+use anchor_normalizer::model::{
+    NormalizedAccountField, NormalizedAccountStruct, NormalizedConstraint, NormalizedInstruction,
+    NormalizedModule, NormalizedParameter, NormalizedProgram, NormalizedRawAccount,
+    NormalizedRawField,
+};
+
+#[test]
+fn test_build_normalized_program_without_io() {
+    let mut instruction = NormalizedInstruction::new("initialize", "pub")
+        .with_account_struct("Initialize")
+        .with_return_type("Result<()>");
+    instruction.add_parameter(NormalizedParameter::new("ctx", "Context<Initialize>", true));
+
+    let mut module = NormalizedModule::new("counter", "pub");
+    module.add_instruction(instruction);
+
+    let mut counter_field = NormalizedAccountField::new("counter", "Account<'info, Counter>");
+    counter_field.add_constraint(NormalizedConstraint::without_value("init", false));
+    counter_field.add_constraint(NormalizedConstraint::with_value(
+        "payer",
+        "authority",
+        false,
+    ));
+
+    let mut account = NormalizedAccountStruct::new("Initialize", "pub");
+    account.add_field(counter_field);
+
+    let mut raw_account = NormalizedRawAccount::new("Counter", "pub");
+    raw_account.add_field(NormalizedRawField::new("count", "u64", "pub"));
+
+    let mut program = NormalizedProgram::new("program:counter", "counter");
+    program.add_module(module);
+    program.add_account_struct(account);
+    program.add_raw_account(raw_account);
+
+    assert!(program.source_info.is_none());
+    assert!(program.find_instruction("initialize").is_some());
+
+    let counter = program
+        .find_account_struct("Initialize")
+        .and_then(|a| a.find_field("counter"))
+        .unwrap();
+    assert!(counter.inferred_info.is_initialized);
+    assert_eq!(
+        program
+            .resolve_field_account(counter)
+            .map(|raw| raw.name.as_str()),
+        Some("Counter")
+    );
+    assert_eq!(
+        program.find_raw_account("Counter").unwrap().layout_size(),
+        Some(8)
+    );
+    #[cfg(feature = "serde")]
+    assert_eq!(program.fingerprint().unwrap().len(), 64);
+}
@@ -7,8 +7,11 @@
 mod fixtures;
 mod helpers;
 
-use anchor_normalizer::{normalize, BasicOperation};
-use fixtures::{create_invalid_program, hello_world_program, token_program};
+use anchor_normalizer::model::{ProgramKind, SeedElement, SCHEMA_VERSION};
+use anchor_normalizer::{normalize, normalize_with_config, BasicOperation, NormalizeConfig};
+use fixtures::{
+    create_invalid_program, example_program, hello_world_program, token_program, vault_program,
+};
 use helpers::*;
 
 /// Basic programs test the core functionality of the normalizer
@@ -76,6 +79,396 @@ mod basic_programs {
             );
         }
     }
+
+    #[test]
+    fn test_declaration_index_across_modules() {
+        let mut program = hello_world_program();
+        let mut admin = anchor_parser::model::ProgramModule::new("admin", "pub");
+        for name in ["pause", "resume"] {
+            admin.add_instruction(
+                anchor_parser::model::Instruction::new(name, "pub").with_parameter(
+                    anchor_parser::model::Parameter::new_context("ctx", "Initialize"),
+                ),
+            );
+        }
+        program.add_program_module(admin);
+
+        let normalized = normalize(&program).unwrap();
+        let indices: Vec<(&str, usize)> = normalized
+            .all_instructions()
+            .map(|i| (i.name.as_str(), i.declaration_index))
+            .collect();
+        assert_eq!(
+            indices,
+            vec![("initialize", 0), ("pause", 1), ("resume", 2)]
+        );
+    }
+}
+
+/// Programs parsed from source to check attribute propagation
+mod parsed_programs {
+    use super::*;
+    use anchor_parser::parse_str;
+
+    #[test]
+    fn test_interface_instruction() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[program]
+            pub mod transfer_hook {
+                use super::*;
+
+                #[interface(spl_transfer_hook_interface::execute)]
+                pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
+                    Ok(())
+                }
+
+                pub fn initialize(ctx: Context<Execute>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Execute {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let execute = normalized.find_instruction("execute").unwrap();
+        assert!(
+            execute.is_interface,
+            "execute should be an interface instruction"
+        );
+
+        let initialize = normalized.find_instruction("initialize").unwrap();
+        assert!(
+            !initialize.is_interface,
+            "initialize should not be an interface instruction"
+        );
+    }
+
+    #[test]
+    fn test_optional_parameters() {
+        let source = r#"
+            #[program]
+            pub mod payments {
+                use super::*;
+
+                pub fn pay(ctx: Context<Pay>, amount: u64, memo: Option<String>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Pay {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let pay = normalized.find_instruction("pay").unwrap();
+        let is_optional = |name: &str| {
+            pay.parameters
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| p.is_optional)
+                .unwrap()
+        };
+
+        assert!(is_optional("memo"));
+        assert!(!is_optional("amount"));
+        assert!(!is_optional("ctx"));
+    }
+
+    #[test]
+    fn test_parameter_documentation() {
+        let source = r#"
+            #[program]
+            pub mod payments {
+                use super::*;
+
+                pub fn pay(
+                    ctx: Context<Pay>,
+                    /// Lamports to send
+                    /// (before fees)
+                    amount: u64,
+                    memo: String,
+                ) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Pay {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let pay = normalized.find_instruction("pay").unwrap();
+        let docs = |name: &str| {
+            pay.parameters
+                .iter()
+                .find(|p| p.name == name)
+                .and_then(|p| p.documentation.as_deref())
+        };
+        assert_eq!(docs("amount"), Some("Lamports to send\n(before fees)"));
+        assert_eq!(docs("memo"), None);
+        assert_eq!(docs("ctx"), None);
+    }
+
+    #[test]
+    fn test_find_instruction_qualified() {
+        let source = r#"
+            #[program]
+            pub mod user_program {
+                use super::*;
+
+                pub fn initialize(ctx: Context<InitUser>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[program]
+            pub mod admin_program {
+                use super::*;
+
+                pub fn initialize(ctx: Context<InitAdmin>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct InitUser {}
+
+            #[derive(Accounts)]
+            pub struct InitAdmin {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let account_struct = |path: &str| {
+            normalized
+                .find_instruction_qualified(path)
+                .and_then(|i| i.account_struct_name.as_deref())
+        };
+        assert_eq!(
+            account_struct("admin_program::initialize"),
+            Some("InitAdmin")
+        );
+        assert_eq!(account_struct("user_program::initialize"), Some("InitUser"));
+        assert_eq!(account_struct("other_program::initialize"), None);
+        assert_eq!(account_struct("initialize"), None);
+
+        // The bare-name lookup takes the first module's instruction
+        assert_eq!(
+            normalized
+                .find_instruction("initialize")
+                .and_then(|i| i.account_struct_name.as_deref()),
+            Some("InitUser")
+        );
+    }
+
+    #[test]
+    fn test_normalize_each_module() {
+        let source = r#"
+            #[program]
+            pub mod user_program {
+                use super::*;
+
+                pub fn register(ctx: Context<Register>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[program]
+            pub mod admin_program {
+                use super::*;
+
+                pub fn configure(ctx: Context<Configure>) -> Result<()> {
+                    Ok(())
+                }
+
+                pub fn pause(ctx: Context<Configure>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Register<'info> {
+                pub profile: Account<'info, Profile>,
+            }
+
+            #[derive(Accounts)]
+            pub struct Configure<'info> {
+                pub config: Account<'info, Config>,
+            }
+
+            #[account]
+            pub struct Profile {
+                pub owner: Pubkey,
+            }
+
+            #[account]
+            pub struct Config {
+                pub admin: Pubkey,
+            }
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let programs = anchor_normalizer::normalize_each(&program).unwrap();
+        assert_eq!(programs.len(), 2);
+
+        let names = |items: Vec<&str>| items.join(",");
+        let [user, admin] = &programs[..] else {
+            unreachable!()
+        };
+
+        assert_eq!(user.name, "user_program");
+        assert_eq!(user.modules.len(), 1);
+        assert_eq!(
+            names(
+                user.account_structs
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect()
+            ),
+            "Register"
+        );
+        assert_eq!(
+            names(user.raw_accounts.iter().map(|a| a.name.as_str()).collect()),
+            "Profile"
+        );
+
+        assert_eq!(admin.name, "admin_program");
+        assert_eq!(
+            names(
+                admin
+                    .account_structs
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect()
+            ),
+            "Configure"
+        );
+        assert_eq!(
+            names(admin.raw_accounts.iter().map(|a| a.name.as_str()).collect()),
+            "Config"
+        );
+        assert_eq!(admin.modules[0].instructions.len(), 2);
+
+        // Scoping leaves no dangling references to report
+        for program in [user, admin] {
+            assert!(
+                program
+                    .validation_issues
+                    .iter()
+                    .all(|issue| issue.rule_id == "unconstrained"),
+                "{:?}",
+                program.validation_issues
+            );
+        }
+    }
+
+    #[test]
+    fn test_primary_module() {
+        use anchor_normalizer::normalization::program::primary_module;
+
+        let source = r#"
+            #[program]
+            pub mod user_program {}
+
+            #[program]
+            pub mod admin_program {}
+        "#;
+        let with_path = |path: &str| parse_str(source).unwrap().with_source_path(path);
+
+        // The file stem names the second module
+        let program = with_path("programs/suite/src/admin_program.rs");
+        assert_eq!(primary_module(&program).unwrap().name, "admin_program");
+        assert_eq!(normalize(&program).unwrap().name, "admin_program");
+
+        // A lib.rs goes by its crate directory, with dashes as underscores
+        let program = with_path("programs/admin-program/src/lib.rs");
+        assert_eq!(primary_module(&program).unwrap().name, "admin_program");
+
+        // Otherwise the first module is the primary one
+        let program = with_path("programs/suite/src/lib.rs");
+        assert_eq!(primary_module(&program).unwrap().name, "user_program");
+        let program = parse_str(source).unwrap();
+        assert_eq!(primary_module(&program).unwrap().name, "user_program");
+    }
+
+    #[test]
+    fn test_instruction_call_graph() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                    check_amount(amount)?;
+                    sync_balance(ctx)
+                }
+
+                pub fn sync_balance(ctx: Context<Deposit>) -> Result<()> {
+                    sync_balance(ctx)
+                }
+            }
+
+            fn check_amount(amount: u64) -> Result<()> {
+                Ok(())
+            }
+
+            #[derive(Accounts)]
+            pub struct Deposit {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let graph = normalized.instruction_call_graph();
+
+        // Helpers and self-calls are not edges
+        assert_eq!(graph["deposit"], vec!["sync_balance"]);
+        assert!(graph["sync_balance"].is_empty());
+        assert_eq!(graph.len(), 2);
+    }
+
+    #[test]
+    fn test_instruction_call_graph_ignores_strings_and_cpi() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                    msg!("will not withdraw (ever)");
+                    token::transfer(ctx.accounts.cpi_ctx(), amount)?;
+                    Ok(())
+                }
+
+                pub fn withdraw(ctx: Context<Deposit>) -> Result<()> {
+                    Ok(())
+                }
+
+                pub fn transfer(ctx: Context<Deposit>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Deposit {}
+        "#;
+
+        let program = parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let graph = normalized.instruction_call_graph();
+
+        assert!(graph["deposit"].is_empty());
+    }
 }
 
 /// Complex programs test more advanced features of the normalizer
@@ -126,148 +519,2120 @@ mod complex_programs {
                     name
                 );
             }
-        }
+        }
+
+        #[test]
+        fn test_required_signers() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let transfer = normalized.find_instruction("transfer").unwrap();
+            assert_eq!(transfer.required_signers(&normalized), vec!["authority"]);
+        }
+
+        #[test]
+        fn test_initializes_accounts() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let initialize = normalized.find_instruction("initialize").unwrap();
+            assert!(initialize.initializes_accounts(&normalized));
+
+            let transfer = normalized.find_instruction("transfer").unwrap();
+            assert!(!transfer.initializes_accounts(&normalized));
+        }
+
+        #[test]
+        fn test_referenced_types() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let types = normalized.referenced_types();
+            assert!(types.contains("u64"));
+            assert!(types.contains("Pubkey"));
+            assert!(types.contains("Context<Transfer>"));
+            assert!(types.contains("Account<'info,Mint>"));
+            assert!(!types.contains("Account<'info, Mint>"));
+        }
+
+        #[test]
+        fn test_cpi_signature() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let mint = normalized.find_instruction("mint").unwrap();
+            assert_eq!(
+                mint.cpi_signature(),
+                vec![("amount".to_string(), "u64".to_string())]
+            );
+        }
+
+        #[test]
+        fn test_initialize_account_struct() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let init_account = normalized
+                .find_account_struct("Initialize")
+                .expect("Initialize account struct should exist");
+
+            assert_eq!(
+                init_account.fields.len(),
+                3,
+                "Initialize account should have 3 fields"
+            );
+
+            let mint_field = init_account
+                .find_field("mint")
+                .expect("mint field should exist in Initialize account");
+
+            assert_has_constraint(mint_field, "init", None);
+            assert_has_constraint(mint_field, "payer", Some("authority"));
+        }
+
+        #[test]
+        fn test_rent_affecting_fields() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let init_account = normalized.find_account_struct("Initialize").unwrap();
+            let names: Vec<_> = init_account
+                .rent_affecting_fields()
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect();
+            assert_eq!(names, vec!["mint"]);
+
+            let transfer_account = normalized.find_account_struct("Transfer").unwrap();
+            assert!(transfer_account.rent_affecting_fields().is_empty());
+        }
+
+        #[test]
+        fn test_inferred_operations() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+            let module = &normalized.modules[0];
+
+            // Test initialize has Initialize operation
+            let init_instruction = module
+                .find_instruction("initialize")
+                .expect("initialize instruction should exist");
+
+            assert_has_operation(
+                init_instruction,
+                |op| matches!(op, BasicOperation::Initialize { .. }),
+                "initialize instruction should have an Initialize operation",
+            );
+
+            // Test transfer has Transfer operation
+            let transfer_instruction = module
+                .find_instruction("transfer")
+                .expect("transfer instruction should exist");
+
+            assert_has_operation(
+                transfer_instruction,
+                |op| matches!(op, BasicOperation::Transfer { .. }),
+                "transfer instruction should have a Transfer operation",
+            );
+        }
+
+        #[test]
+        fn test_pubkey_fields() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let pubkey_fields = normalized.pubkey_fields();
+            assert!(pubkey_fields.contains(&("TokenAccount".to_string(), "owner".to_string())));
+            assert!(pubkey_fields.contains(&("Mint".to_string(), "authority".to_string())));
+            assert_eq!(
+                pubkey_fields.len(),
+                2,
+                "Only Pubkey fields should be included"
+            );
+        }
+
+        #[test]
+        fn test_fingerprint() {
+            let first = token_program().with_source_path("programs/a/src/lib.rs");
+            let second = token_program().with_source_path("programs/b/src/lib.rs");
+
+            let first = normalize(&first).unwrap();
+            let second = normalize(&second).unwrap();
+            assert_ne!(first.id, second.id);
+            assert_eq!(first.fingerprint().unwrap(), second.fingerprint().unwrap());
+
+            let mut renamed = token_program();
+            renamed.program_modules[0].instructions[1].name = "mint_to".to_string();
+            let renamed = normalize(&renamed).unwrap();
+            assert_ne!(first.fingerprint().unwrap(), renamed.fingerprint().unwrap());
+        }
+
+        #[test]
+        fn test_renormalize_is_idempotent() {
+            let normalized = normalize(&token_program()).unwrap();
+
+            let program = anchor_parser::Program::from(&normalized);
+            assert_eq!(program.account_structs.len(), 3);
+            let renormalized = normalize(&program).unwrap();
+
+            assert_eq!(
+                normalized.fingerprint().unwrap(),
+                renormalized.fingerprint().unwrap()
+            );
+
+            for name in ["hello_world", "counter", "token_vault", "token_program"] {
+                let normalized = normalize(&example_program(name)).unwrap();
+                let renormalized = normalize(&anchor_parser::Program::from(&normalized)).unwrap();
+                assert_eq!(
+                    normalized.fingerprint().unwrap(),
+                    renormalized.fingerprint().unwrap(),
+                    "{} should round-trip",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_instruction_discriminator() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let initialize = normalized.find_instruction("initialize").unwrap();
+            assert_eq!(
+                initialize.discriminator(),
+                [175, 175, 109, 31, 13, 152, 155, 237]
+            );
+        }
+
+        #[test]
+        fn test_operation_serialization() {
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            let transfer_instruction = normalized
+                .find_instruction("transfer")
+                .expect("transfer instruction should exist");
+            let body = serde_json::to_value(transfer_instruction.body.as_ref().unwrap()).unwrap();
+
+            assert_eq!(body["kind"], "Basic");
+            let transfer = body["operations"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|op| op["kind"] == "Transfer")
+                .expect("Transfer operation should be tagged with kind");
+            assert!(transfer.get("from").is_some());
+            assert!(transfer.get("to").is_some());
+
+            assert_eq!(normalized.schema_version, SCHEMA_VERSION);
+        }
+
+        #[test]
+        fn test_instructions_where() {
+            use anchor_normalizer::model::InstructionBody;
+
+            let program = token_program();
+            let normalized = normalize(&program).unwrap();
+
+            assert_eq!(normalized.all_instructions().count(), 3);
+
+            let initializers: Vec<&str> = normalized
+                .instructions_where(|instruction| match &instruction.body {
+                    Some(InstructionBody::Basic(ops)) => ops
+                        .iter()
+                        .any(|op| matches!(op, BasicOperation::Initialize { .. })),
+                    _ => false,
+                })
+                .iter()
+                .map(|instruction| instruction.name.as_str())
+                .collect();
+            assert_eq!(initializers, vec!["initialize"]);
+        }
+
+        #[test]
+        fn test_rename_account_struct() {
+            let program = token_program();
+            let mut normalized = normalize(&program).unwrap();
+
+            normalized
+                .rename_account_struct("Transfer", "Send")
+                .unwrap();
+
+            assert!(normalized.find_account_struct("Transfer").is_none());
+            assert!(normalized.find_account_struct("Send").is_some());
+
+            let transfer = normalized.find_instruction("transfer").unwrap();
+            assert_eq!(transfer.account_struct_name.as_deref(), Some("Send"));
+            let ctx = &transfer.parameters[0];
+            assert!(
+                ctx.ty.contains("Send"),
+                "Context type should update: {}",
+                ctx.ty
+            );
+            assert!(!ctx.ty.contains("Transfer"));
+
+            // Renaming onto an existing struct fails and leaves the program intact
+            assert!(normalized.rename_account_struct("Send", "Mint").is_err());
+            assert!(normalized.find_account_struct("Send").is_some());
+        }
+    }
+}
+
+/// Tests for the validation features of the normalizer
+mod validation {
+    use super::*;
+    use anchor_normalizer::model::validation::IssueSeverity;
+    use anchor_parser::model::{
+        Account, AccountField, Constraint, Instruction, Parameter, Program, ProgramModule,
+        RawAccount, RawAccountField,
+    };
+
+    #[test]
+    fn test_duplicate_account_struct() {
+        // Create a program with validation issues
+        let mut program = hello_world_program();
+
+        // Add a duplicate account struct
+        let account = Account::new("Initialize", "pub");
+        program.add_account_struct(account);
+
+        // Normalize it
+        let normalized = normalize(&program).unwrap();
+
+        // Check for validation issues
+        assert!(
+            !normalized.validation_issues.is_empty(),
+            "Should have validation issues with duplicate account struct"
+        );
+        assert_validation_issue(&normalized, "Duplicate account struct name");
+    }
+
+    #[test]
+    fn test_duplicate_field_name() {
+        let mut program = hello_world_program();
+
+        // Add an account struct with two authority fields
+        let mut account = Account::new("Update", "pub");
+        account.add_field(AccountField::new("authority", "Signer<'info>"));
+        account.add_field(AccountField::new("authority", "Signer<'info>"));
+        program.add_account_struct(account);
+
+        let normalized = normalize(&program).unwrap();
+
+        let duplicates: Vec<_> = normalized
+            .validation_issues
+            .iter()
+            .filter(|issue| issue.message.contains("Duplicate field name"))
+            .collect();
+        assert_eq!(duplicates.len(), 1, "Should report one duplicate field");
+        assert_eq!(duplicates[0].severity, IssueSeverity::Error);
+        assert_eq!(duplicates[0].element, "Update.authority");
+    }
+
+    #[test]
+    fn test_unknown_account_type_reference() {
+        let mut program = hello_world_program();
+
+        let mut account = Account::new("Touch", "pub");
+        account.add_field(AccountField::new("thing", "Account<'info, ExternalThing>"));
+        account.add_field(AccountField::new("mint", "Account<'info, Mint>"));
+        program.add_account_struct(account);
+
+        let normalized = normalize(&program).unwrap();
+        assert_validation_issue(&normalized, "unknown account type ExternalThing");
+        assert!(
+            !normalized
+                .validation_issues
+                .iter()
+                .any(|issue| issue.message.contains("unknown account type Mint")),
+            "Built-in types should not be flagged"
+        );
+
+        // Configured known types are not flagged
+        let config = NormalizeConfig::new().with_known_types(["ExternalThing"]);
+        let normalized = normalize_with_config(&program, &config).unwrap();
+        assert!(
+            !normalized
+                .validation_issues
+                .iter()
+                .any(|issue| issue.message.contains("unknown account type")),
+            "Known types should not be flagged: {:?}",
+            normalized.validation_issues
+        );
+    }
+
+    #[test]
+    fn test_warn_unknown_bodies() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[program]
+            pub mod custom {
+                use super::*;
+
+                pub fn crank(ctx: Context<Crank>) -> Result<()> {
+                    run_custom_logic(&ctx)?;
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Crank<'info> {
+                pub authority: Signer<'info>,
+            }
+        "#;
+        let program = anchor_parser::parse_str(source).unwrap();
+
+        // Off by default
+        let normalized = normalize(&program).unwrap();
+        assert!(!normalized
+            .validation_issues
+            .iter()
+            .any(|issue| issue.message.contains("could not be analyzed")));
+
+        let config = NormalizeConfig::new().with_warn_unknown_bodies(true);
+        let normalized = normalize_with_config(&program, &config).unwrap();
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message.contains("could not be analyzed"))
+            .expect("unknown body should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Info);
+        assert_eq!(issue.element, "crank");
+    }
+
+    #[test]
+    fn test_missing_payer_field() {
+        use anchor_normalizer::model::InstructionBody;
+
+        let source = r#"
+            #[program]
+            pub mod counter {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                #[account(init, payer = ghost, space = 8 + 8)]
+                pub counter: Account<'info, Counter>,
+                pub system_program: Program<'info, System>,
+            }
+
+            #[account]
+            pub struct Counter {
+                pub count: u64,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        // The operation keeps the payer as written
+        let initialize = normalized.find_instruction("initialize").unwrap();
+        match &initialize.body {
+            Some(InstructionBody::Basic(operations)) => assert!(operations.iter().any(|op| matches!(
+                op,
+                BasicOperation::Initialize { target, payer } if target == "counter" && payer == "ghost"
+            ))),
+            other => panic!("Expected inferred operations, got {:?}", other),
+        }
+
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message.contains("payer ghost"))
+            .expect("missing payer should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.element, "Initialize.counter");
+        assert_eq!(issue.rule_id, "payer");
+    }
+
+    #[test]
+    fn test_realloc_without_payer() {
+        use anchor_normalizer::model::InstructionBody;
+
+        let source = r#"
+            #[program]
+            pub mod profile {
+                use super::*;
+
+                pub fn grow(ctx: Context<Grow>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Grow<'info> {
+                #[account(mut, realloc = 100, realloc::zero = false)]
+                pub profile: Account<'info, Profile>,
+                #[account(mut)]
+                pub payer: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        // No payer is made up for the operation
+        let grow = normalized.find_instruction("grow").unwrap();
+        match &grow.body {
+            Some(InstructionBody::Basic(operations)) => {
+                assert!(operations.contains(&BasicOperation::Realloc {
+                    target: "profile".to_string(),
+                    payer: None,
+                }))
+            }
+            other => panic!("Expected inferred operations, got {:?}", other),
+        }
+
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message.contains("without a realloc::payer"))
+            .expect("missing realloc::payer should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.element, "Grow.profile");
+        assert_eq!(issue.rule_id, "payer");
+    }
+
+    #[test]
+    fn test_merged_files_resolve_account_structs() {
+        let handlers = r#"
+            use anchor_lang::prelude::*;
+            use crate::instructions::*;
+
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+                    Ok(())
+                }
+            }
+        "#;
+        let accounts = r#"
+            use anchor_lang::prelude::*;
+
+            #[derive(Accounts)]
+            pub struct Transfer<'info> {
+                #[account(mut)]
+                pub from: Signer<'info>,
+                #[account(mut)]
+                pub to: SystemAccount<'info>,
+            }
+        "#;
+
+        let undefined_warning = |program: &Program| {
+            normalize(program)
+                .unwrap()
+                .validation_issues
+                .iter()
+                .any(|issue| issue.message.contains("undefined account struct Transfer"))
+        };
+
+        let mut program = anchor_parser::parse_str(handlers).unwrap();
+        assert!(
+            undefined_warning(&program),
+            "a lone handler file can't resolve Transfer"
+        );
+
+        program.merge(anchor_parser::parse_str(accounts).unwrap());
+        assert!(!undefined_warning(&program));
+
+        let normalized = normalize(&program).unwrap();
+        let transfer = normalized.find_instruction("transfer").unwrap();
+        assert_eq!(transfer.required_signers(&normalized), vec!["from"]);
+    }
+
+    #[test]
+    fn test_instruction_arg_shadows_field() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn open(ctx: Context<Open>, bump: u8) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            #[instruction(bump: u8)]
+            pub struct Open<'info> {
+                #[account(mut)]
+                pub bump: Signer<'info>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.rule_id == "shadowing")
+            .expect("shadowed field should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.element, "Open.bump");
+        assert!(issue
+            .message
+            .contains("Instruction argument bump shadows field bump"));
+    }
+
+    #[test]
+    fn test_close_target_requires_mut() {
+        let normalize_close = |attrs: &str| {
+            let source = format!(
+                r#"
+                #[program]
+                pub mod counter {{
+                    use super::*;
+
+                    pub fn close(ctx: Context<Close>) -> Result<()> {{
+                        Ok(())
+                    }}
+                }}
+
+                #[derive(Accounts)]
+                pub struct Close<'info> {{
+                    #[account({attrs})]
+                    pub counter: Account<'info, Counter>,
+                    #[account(mut)]
+                    pub authority: Signer<'info>,
+                }}
+
+                #[account]
+                pub struct Counter {{
+                    pub count: u64,
+                }}
+            "#
+            );
+            let program = anchor_parser::parse_str(&source).unwrap();
+            normalize(&program).unwrap()
+        };
+
+        let normalized = normalize_close("close = authority");
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message.contains("closed but not mut"))
+            .expect("immutable close target should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.element, "Close.counter");
+
+        let normalized = normalize_close("mut, close = authority");
+        assert!(!normalized
+            .validation_issues
+            .iter()
+            .any(|issue| issue.message.contains("closed but not mut")));
+    }
+
+    #[test]
+    fn test_unchecked_account_check_doc() {
+        use anchor_normalizer::model::FieldKind;
+
+        let normalize_with_docs = |docs: &str| {
+            let source = format!(
+                r#"
+                #[program]
+                pub mod relay {{
+                    use super::*;
+
+                    pub fn forward(ctx: Context<Forward>) -> Result<()> {{
+                        Ok(())
+                    }}
+                }}
+
+                #[derive(Accounts)]
+                pub struct Forward<'info> {{
+                    {}
+                    pub target: UncheckedAccount<'info>,
+                    pub signer: Signer<'info>,
+                }}
+                "#,
+                docs
+            );
+            normalize(&anchor_parser::parse_str(&source).unwrap()).unwrap()
+        };
+
+        let missing = normalize_with_docs("/// The account to forward to");
+        let target = missing
+            .find_account_struct("Forward")
+            .and_then(|a| a.find_field("target"))
+            .unwrap();
+        assert_eq!(target.inferred_info.kind, FieldKind::Unchecked);
+        let issue = missing
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message.contains("no /// CHECK: doc comment"))
+            .expect("missing CHECK doc should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.element, "Forward.target");
+
+        let documented = normalize_with_docs("/// CHECK: only forwarded to the CPI target");
+        assert_eq!(
+            documented
+                .find_account_struct("Forward")
+                .and_then(|a| a.find_field("target"))
+                .and_then(|f| f.documentation.as_deref()),
+            Some("CHECK: only forwarded to the CPI target")
+        );
+        assert!(!documented
+            .validation_issues
+            .iter()
+            .any(|issue| issue.message.contains("CHECK")));
+    }
+
+    #[test]
+    fn test_unreferenced_raw_account() {
+        let mut program = vault_program(&[("authority", "Pubkey")]);
+
+        let mut ghost = RawAccount::new("Ghost", "pub");
+        ghost.add_field(RawAccountField::new("value", "u64", "pub"));
+        program.add_raw_account(ghost);
+
+        let normalized = normalize(&program).unwrap();
+
+        let unreferenced: Vec<&str> = normalized
+            .unreferenced_raw_accounts()
+            .iter()
+            .map(|raw| raw.name.as_str())
+            .collect();
+        assert_eq!(unreferenced, vec!["Ghost"]);
+
+        assert_validation_issue(&normalized, "Raw account Ghost is not referenced");
+        assert!(
+            !normalized
+                .validation_issues
+                .iter()
+                .any(|issue| issue.message.contains("Raw account Vault")),
+            "Referenced raw accounts should not be flagged"
+        );
+    }
+
+    #[test]
+    fn test_issue_ordering_is_deterministic() {
+        let mut program = vault_program(&[("authority", "u64")]);
+        program.add_account_struct(Account::new("Withdraw", "pub"));
+        let mut account = Account::new("Touch", "pub");
+        account.add_field(AccountField::new("thing", "Account<'info, ExternalThing>"));
+        program.add_account_struct(account);
+
+        let first = normalize(&program).unwrap();
+        let second = normalize(&program).unwrap();
+
+        let summarize = |issues: &[anchor_normalizer::model::ValidationIssue]| {
+            issues
+                .iter()
+                .map(|issue| (issue.severity, issue.element.clone(), issue.message.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert!(first.validation_issues.len() >= 3);
+        assert_eq!(
+            summarize(&first.validation_issues),
+            summarize(&second.validation_issues)
+        );
+
+        // Errors come first, and elements are ordered within a severity
+        assert_eq!(first.validation_issues[0].severity, IssueSeverity::Error);
+        for pair in first.validation_issues.windows(2) {
+            assert!(
+                pair[0].severity > pair[1].severity
+                    || (pair[0].severity == pair[1].severity && pair[0].element <= pair[1].element),
+                "Issues should be sorted: {:?}",
+                first.validation_issues
+            );
+        }
+    }
+
+    #[test]
+    fn test_issue_counts() {
+        let mut program = vault_program(&[("authority", "u64")]);
+        // Duplicate account struct: error
+        program.add_account_struct(Account::new("Withdraw", "pub"));
+        // Raw account no field wraps: info
+        program.add_raw_account(RawAccount::new("Ghost", "pub"));
+
+        let normalized = normalize(&program).unwrap();
+        let counts = normalized.issue_counts();
+
+        // The has_one target isn't a Pubkey: warning
+        assert_eq!(
+            (counts.errors, counts.warnings, counts.info),
+            (1, 1, 1),
+            "{:?}",
+            normalized.validation_issues
+        );
+        assert_eq!(counts.to_string(), "1 error, 1 warning, 1 info");
+
+        let clean = normalize(&hello_world_program()).unwrap();
+        assert_eq!(
+            clean.issue_counts().to_string(),
+            "0 errors, 0 warnings, 0 info"
+        );
+    }
+
+    #[test]
+    fn test_reserved_instruction_name() {
+        let mut program = token_program();
+        program.program_modules[0].add_instruction(
+            Instruction::new("idl", "pub")
+                .with_parameter(Parameter::new_context("ctx", "Initialize"))
+                .with_return_type("Result<()>")
+                .with_context_type("Initialize"),
+        );
+
+        let normalized = normalize(&program).unwrap();
+        assert_validation_issue(
+            &normalized,
+            "Instruction idl uses a name reserved by Anchor",
+        );
+        assert!(
+            !normalized
+                .validation_issues
+                .iter()
+                .any(|issue| issue.element == "transfer" && issue.message.contains("reserved")),
+            "transfer should not be reported as reserved"
+        );
+    }
+
+    #[test]
+    fn test_missing_account_struct() {
+        // Create a fresh program with a non-existent account struct reference
+        let mut program = Program::new();
+
+        // Add a program module
+        let mut module = ProgramModule::new("test_program", "pub");
+
+        // Add an instruction that references a non-existent account struct
+        let instruction = Instruction::new("initialize", "pub")
+            .with_parameter(Parameter::new_context("ctx", "NonExistentStruct"))
+            .with_return_type("Result<()>")
+            .with_context_type("NonExistentStruct");
+
+        module.add_instruction(instruction);
+        program.add_program_module(module);
+
+        // Normalize it
+        let normalized = normalize(&program).unwrap();
+
+        // Print all validation issues to help debug
+        println!("Validation issues: {:?}", normalized.validation_issues);
+
+        // Check for validation issues - look for "undefined account struct" instead
+        assert_validation_issue(&normalized, "undefined account struct");
+    }
+
+    #[test]
+    fn test_has_one_target_is_pubkey() {
+        let program = vault_program(&[("authority", "Pubkey"), ("amount", "u64")]);
+        let normalized = normalize(&program).unwrap();
+
+        let vault = normalized
+            .find_account_struct("Withdraw")
+            .and_then(|a| a.find_field("vault"))
+            .expect("Should find vault field");
+        let raw = normalized
+            .resolve_field_account(vault)
+            .expect("Should resolve Vault raw account");
+        assert_eq!(raw.name, "Vault");
+
+        assert!(
+            !normalized
+                .validation_issues
+                .iter()
+                .any(|issue| issue.message.contains("has_one")),
+            "Pubkey has_one target should not produce issues: {:?}",
+            normalized.validation_issues
+        );
+    }
+
+    #[test]
+    fn test_has_one_target_wrong_type() {
+        let program = vault_program(&[("authority", "u64"), ("amount", "u64")]);
+        let normalized = normalize(&program).unwrap();
+
+        assert_validation_issue(&normalized, "has_one target authority");
+        assert_validation_issue(&normalized, "expected Pubkey");
+    }
+
+    #[test]
+    fn test_has_one_target_missing_field() {
+        let program = vault_program(&[("amount", "u64")]);
+        let normalized = normalize(&program).unwrap();
+
+        assert_validation_issue(&normalized, "has_one target authority");
+        assert_validation_issue(&normalized, "is not a field of Vault");
+    }
+
+    #[test]
+    fn test_constraint_error_codes() {
+        let source = r#"
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Withdraw<'info> {
+                #[account(
+                    mut @ VaultError::NotMutable,
+                    has_one = authority @ VaultError::Unauthorized,
+                    constraint = vault.amount > 0 @ VaultError::TooSmall,
+                    constraint = vault.label == "a @ b",
+                )]
+                pub vault: Account<'info, Vault>,
+                pub authority: Signer<'info>,
+            }
+
+            #[account]
+            pub struct Vault {
+                pub authority: Pubkey,
+                pub amount: u64,
+                pub label: String,
+            }
+
+            #[error_code]
+            pub enum VaultError {
+                TooSmall,
+                Unauthorized,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let vault = normalized
+            .find_account_struct("Withdraw")
+            .and_then(|a| a.find_field("vault"))
+            .unwrap();
+
+        let constraints: Vec<(&str, Option<&str>, Option<&str>)> = vault
+            .constraints
+            .iter()
+            .filter(|c| !c.is_inferred)
+            .map(|c| {
+                (
+                    c.constraint_type.as_str(),
+                    c.value.as_deref(),
+                    c.error_code.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            constraints,
+            [
+                ("mut", None, Some("VaultError::NotMutable")),
+                (
+                    "has_one",
+                    Some("authority"),
+                    Some("VaultError::Unauthorized")
+                ),
+                (
+                    "constraint",
+                    Some("vault.amount > 0"),
+                    Some("VaultError::TooSmall")
+                ),
+                ("constraint", Some(r#"vault.label == "a @ b""#), None),
+            ]
+        );
+        assert!(vault.inferred_info.requires_mut);
+        assert_eq!(
+            normalized.error_enums[0].variants,
+            ["TooSmall", "Unauthorized"]
+        );
+
+        // NotMutable isn't a variant of the parsed VaultError
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.rule_id == "error-code")
+            .expect("Unknown error variant should be reported");
+        assert_eq!(
+            issue.message,
+            "Constraint mut on field vault in account Withdraw maps to VaultError::NotMutable, which is not a variant of VaultError"
+        );
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(
+            normalized
+                .validation_issues
+                .iter()
+                .filter(|issue| issue.rule_id == "error-code")
+                .count(),
+            1
+        );
+
+        // Error codes survive regenerating the source and the reverse conversion
+        let regenerated = anchor_normalizer::export::to_anchor_source(&normalized);
+        assert!(regenerated.contains("mut @ VaultError::NotMutable"));
+        let reparsed = normalize(&anchor_parser::parse_str(&regenerated).unwrap()).unwrap();
+        assert_eq!(
+            reparsed.fingerprint().unwrap(),
+            normalized.fingerprint().unwrap()
+        );
+        let converted = normalize(&anchor_parser::Program::from(&normalized)).unwrap();
+        assert_eq!(
+            converted.fingerprint().unwrap(),
+            normalized.fingerprint().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_validation_rule() {
+        use anchor_normalizer::model::{
+            NormalizedInstruction, NormalizedModule, NormalizedProgram, ValidationIssue,
+        };
+        use anchor_normalizer::normalization::validation::{
+            default_rules, validate_with, ValidationRule,
+        };
+
+        struct DangerRule;
+
+        impl ValidationRule for DangerRule {
+            fn validate(&self, program: &NormalizedProgram, issues: &mut Vec<ValidationIssue>) {
+                for instruction in program.all_instructions() {
+                    if instruction.name == "danger" {
+                        issues.push(
+                            ValidationIssue::warning(
+                                "Instruction danger is forbidden in this project",
+                                instruction.name.clone(),
+                            )
+                            .with_rule("no-danger"),
+                        );
+                    }
+                }
+            }
+        }
+
+        let mut program = NormalizedProgram::new("program:test", "test");
+        let mut module = NormalizedModule::new("test", "pub");
+        module.add_instruction(
+            NormalizedInstruction::new("danger", "pub").with_account_struct("Missing"),
+        );
+        module.add_instruction(NormalizedInstruction::new("safe", "pub"));
+        program.add_module(module);
+
+        let mut rules = default_rules();
+        rules.push(Box::new(DangerRule));
+        validate_with(&mut program, &rules).unwrap();
+
+        let rule_ids: Vec<&str> = program
+            .validation_issues
+            .iter()
+            .map(|issue| issue.rule_id.as_str())
+            .collect();
+        assert_eq!(
+            rule_ids.iter().filter(|id| **id == "no-danger").count(),
+            1,
+            "Custom rule should flag only danger"
+        );
+        assert!(
+            program
+                .validation_issues
+                .iter()
+                .any(|issue| issue.rule_id != "no-danger" && issue.element == "danger"),
+            "Default rules should still report the missing account struct: {:?}",
+            rule_ids
+        );
+    }
+
+    #[test]
+    fn test_keyword_constraint_error_codes() {
+        let source = r#"
+            #[program]
+            pub mod guarded {
+                use super::*;
+
+                pub fn touch(ctx: Context<Touch>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Touch<'info> {
+                #[account(mut @ MyError::NotMutable)]
+                pub data: Account<'info, Data>,
+                #[account(signer @ MyError::NotSigner)]
+                pub admin: AccountInfo<'info>,
+            }
+
+            #[account]
+            pub struct Data {
+                pub value: u64,
+            }
+
+            #[error_code]
+            pub enum MyError {
+                NotMutable,
+                NotSigner,
+            }
+        "#;
+
+        let normalized = normalize(&anchor_parser::parse_str(source).unwrap()).unwrap();
+        let account = normalized.find_account_struct("Touch").unwrap();
+
+        let data = account.find_field("data").unwrap();
+        let constraint = data
+            .find_constraint("mut")
+            .expect("mut should be split off");
+        assert_eq!(constraint.value, None);
+        assert_eq!(
+            constraint.error_code.as_deref(),
+            Some("MyError::NotMutable")
+        );
+        assert!(data.inferred_info.requires_mut);
+
+        let admin = account.find_field("admin").unwrap();
+        let constraint = admin
+            .find_constraint("signer")
+            .expect("signer should be split off");
+        assert_eq!(constraint.error_code.as_deref(), Some("MyError::NotSigner"));
+        assert!(admin.inferred_info.requires_signer);
+
+        assert!(!normalized
+            .validation_issues
+            .iter()
+            .any(|issue| issue.rule_id == "error-code"));
+    }
+
+    #[test]
+    fn test_unconstrained_fields() {
+        let mut program = token_program();
+        program.account_structs[0]
+            .add_field(AccountField::new("receiver", "UncheckedAccount<'info>"));
+        let normalized = normalize(&program).unwrap();
+        let initialize = normalized.find_account_struct("Initialize").unwrap();
+
+        let unconstrained: Vec<&str> = initialize
+            .unconstrained_fields()
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(unconstrained, ["system_program", "receiver"]);
+
+        let reported: Vec<&str> = normalized
+            .validation_issues
+            .iter()
+            .filter(|issue| issue.rule_id == "unconstrained")
+            .map(|issue| issue.element.as_str())
+            .collect();
+        // Program fields are checked by their type alone
+        assert!(!reported.contains(&"Initialize.system_program"));
+        assert!(reported.contains(&"Initialize.receiver"));
+        assert!(normalized
+            .validation_issues
+            .iter()
+            .filter(|issue| issue.rule_id == "unconstrained")
+            .all(|issue| issue.severity == IssueSeverity::Info));
+    }
+
+    #[test]
+    fn test_has_one_target_missing() {
+        let mut program = vault_program(&[("authority", "Pubkey")]);
+        program.account_structs[0].fields[0]
+            .add_constraint(Constraint::with_value("has_one", "manager"));
+        let normalized = normalize(&program).unwrap();
+
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| {
+                issue.message == "has_one target manager on field vault in account Withdraw is not a field of Vault"
+            })
+            .expect("Missing has_one target should be reported");
+        assert_eq!(issue.severity, IssueSeverity::Error);
+        assert_eq!(issue.element, "Withdraw.vault");
+        assert_eq!(issue.rule_id, "has-one");
+    }
+}
+
+/// Tests for the inference features of the normalizer
+mod inference {
+    use super::*;
+
+    #[test]
+    fn test_mut_inferred_from_init() {
+        let program = token_program();
+        let normalized = normalize(&program).unwrap();
+
+        let init_account = normalized
+            .find_account_struct("Initialize")
+            .expect("Initialize account struct should exist");
+
+        let mint_field = init_account
+            .find_field("mint")
+            .expect("mint field should exist");
+
+        // The field should have both init and mut constraints
+        assert_has_constraint(mint_field, "init", None);
+        assert!(
+            mint_field.inferred_info.is_initialized,
+            "Field should be marked as initialized"
+        );
+
+        // Check if mut was either present or inferred
+        let has_mut_constraint = mint_field
+            .constraints
+            .iter()
+            .any(|c| c.constraint_type == "mut");
+
+        assert!(
+            has_mut_constraint || mint_field.inferred_info.requires_mut,
+            "Field should have explicit or inferred mut constraint"
+        );
+    }
+
+    #[test]
+    fn test_mut_inferred_for_payer() {
+        let source = r#"
+            #[program]
+            pub mod counter {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                #[account(init, payer = authority, space = 8 + 8)]
+                pub counter: Account<'info, Counter>,
+                pub authority: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let account = normalized.find_account_struct("Initialize").unwrap();
+
+        let authority = account.find_field("authority").unwrap();
+        let mut_constraint = authority
+            .find_constraint("mut")
+            .expect("payer should get a mut constraint");
+        assert!(mut_constraint.is_inferred);
+        assert!(authority.inferred_info.requires_mut);
+
+        let system_program = account.find_field("system_program").unwrap();
+        assert!(!system_program.inferred_info.requires_mut);
+    }
+
+    #[test]
+    fn test_realloc_sub_constraints() {
+        use anchor_normalizer::model::InstructionBody;
+
+        let source = r#"
+            #[program]
+            pub mod profile {
+                use super::*;
+
+                pub fn grow(ctx: Context<Grow>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Grow<'info> {
+                #[account(mut, realloc = 100, realloc::payer = user, realloc::zero = false)]
+                pub profile: Account<'info, Profile>,
+                #[account(mut)]
+                pub user: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let profile = normalized
+            .find_account_struct("Grow")
+            .and_then(|a| a.find_field("profile"))
+            .unwrap();
+        assert_has_constraint(profile, "realloc", Some("100"));
+        assert_eq!(profile.inferred_info.realloc_payer.as_deref(), Some("user"));
+        assert!(!profile.inferred_info.realloc_zero);
+
+        let grow = normalized.find_instruction("grow").unwrap();
+        match &grow.body {
+            Some(InstructionBody::Basic(operations)) => {
+                assert!(operations.iter().any(|op| matches!(
+                op,
+                BasicOperation::Realloc { target, payer } if target == "profile" && payer.as_deref() == Some("user")
+            )))
+            }
+            other => panic!("Expected inferred operations, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_configured_authority_field_names() {
+        let source = r#"
+            #[program]
+            pub mod dao {
+                use super::*;
+
+                pub fn propose(ctx: Context<Propose>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Propose<'info> {
+                pub governance: Signer<'info>,
+                pub authority: Signer<'info>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let inferred_signer = |config: &NormalizeConfig, name: &str| {
+            let normalized = normalize_with_config(&program, config).unwrap();
+            normalized
+                .find_account_struct("Propose")
+                .and_then(|a| a.find_field(name))
+                .and_then(|f| f.find_constraint("signer"))
+                .is_some_and(|c| c.is_inferred)
+        };
+
+        let default = NormalizeConfig::new();
+        assert!(inferred_signer(&default, "authority"));
+        assert!(!inferred_signer(&default, "governance"));
+
+        let config = NormalizeConfig::new().with_authority_field_names(["governance"]);
+        assert!(inferred_signer(&config, "governance"));
+        assert!(
+            inferred_signer(&config, "authority"),
+            "default names should still apply"
+        );
+    }
+
+    #[test]
+    fn test_has_one_relationship_in_token_vault() {
+        let program = example_program("token_vault");
+        let normalized = normalize(&program).unwrap();
+
+        let deposit = normalized
+            .find_account_struct("Deposit")
+            .expect("Deposit account struct should exist");
+        let vault = deposit
+            .find_field("vault")
+            .expect("vault field should exist");
+
+        assert_eq!(
+            vault.inferred_info.related_account,
+            Some("authority".to_string())
+        );
+    }
+
+    #[test]
+    fn test_has_one_relationship_with_mut() {
+        // The vault fixture combines mut with has_one = authority
+        let program = vault_program(&[("authority", "Pubkey")]);
+        let normalized = normalize(&program).unwrap();
+        let vault = normalized
+            .find_account_struct("Withdraw")
+            .and_then(|a| a.find_field("vault"))
+            .expect("vault field should exist");
+
+        assert!(vault.inferred_info.requires_mut);
+        assert_eq!(
+            vault.inferred_info.related_account,
+            Some("authority".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pda_seed_parsing() {
+        let source = r#"
+            #[program]
+            pub mod vaults {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                #[account(init, payer = authority, space = 8 + 33, seeds = [b"vault", authority.key().as_ref()], bump)]
+                pub vault: Account<'info, Vault>,
+                #[account(mut)]
+                pub authority: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let vault = normalized
+            .find_account_struct("Initialize")
+            .and_then(|a| a.find_field("vault"))
+            .expect("vault field should exist");
+        let pda = vault
+            .inferred_info
+            .pda
+            .as_ref()
+            .expect("vault should be a PDA");
+
+        assert_eq!(
+            pda.seeds,
+            vec![
+                SeedElement::Literal(vec![118, 97, 117, 108, 116]),
+                SeedElement::AccountRef("authority".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pda_bump_source() {
+        use anchor_normalizer::model::BumpSource;
+
+        let source = r#"
+            #[program]
+            pub mod vaults {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+
+                pub fn deposit(ctx: Context<Deposit>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                #[account(init, payer = authority, space = 8 + 33, seeds = [b"state"], bump)]
+                pub state: Account<'info, State>,
+                #[account(mut)]
+                pub authority: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+
+            #[derive(Accounts)]
+            pub struct Deposit<'info> {
+                #[account(mut, seeds = [b"state"], bump = state.bump)]
+                pub state: Account<'info, State>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let bump_source = |account: &str| {
+            normalized
+                .find_account_struct(account)
+                .and_then(|a| a.find_field("state"))
+                .and_then(|f| f.inferred_info.pda.as_ref())
+                .map(|pda| pda.bump_source.clone())
+                .expect("state should be a PDA")
+        };
+
+        assert_eq!(bump_source("Initialize"), BumpSource::Canonical);
+        assert_eq!(
+            bump_source("Deposit"),
+            BumpSource::Stored("state.bump".to_string())
+        );
+    }
+
+    #[test]
+    fn test_stored_bump_field() {
+        use anchor_parser::model::Constraint;
+
+        // token_vault stores the vault bump; have Deposit re-derive with it
+        let mut program = example_program("token_vault");
+        let deposit = program
+            .account_structs
+            .iter_mut()
+            .find(|a| a.name == "Deposit")
+            .unwrap();
+        let vault = deposit
+            .fields
+            .iter_mut()
+            .find(|f| f.name == "vault")
+            .unwrap();
+        vault.add_constraint(Constraint::with_value("seeds", r#"[b"vault"]"#));
+        vault.add_constraint(Constraint::with_value("bump", "vault.bump"));
+        let authority = deposit
+            .fields
+            .iter_mut()
+            .find(|f| f.name == "authority")
+            .unwrap();
+        authority.add_constraint(Constraint::with_value("seeds", r#"[b"auth"]"#));
+        authority.add_constraint(Constraint::with_value("bump", "authority.bump"));
+
+        let normalized = normalize(&program).unwrap();
+        let pda = |field: &str| {
+            normalized
+                .find_account_struct("Deposit")
+                .and_then(|a| a.find_field(field))
+                .and_then(|f| f.inferred_info.pda.clone())
+                .expect("field should be a PDA")
+        };
+
+        assert_eq!(
+            pda("vault").stored_bump_field,
+            Some(("Vault".to_string(), "bump".to_string()))
+        );
+        // A Signer wraps no account data to read the bump from
+        assert_eq!(pda("authority").stored_bump_field, None);
+    }
+
+    #[test]
+    fn test_init_pda_pattern() {
+        use anchor_normalizer::model::{BasicOperation, BumpSource, InstructionBody, SeedElement};
+
+        let source = r#"
+            #[program]
+            pub mod vaults {
+                use super::*;
+
+                pub fn create(ctx: Context<Create>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Create<'info> {
+                #[account(
+                    init,
+                    payer = owner,
+                    space = 8 + Vault::INIT_SPACE,
+                    seeds = [b"vault", owner.key().as_ref()],
+                    bump
+                )]
+                pub vault: Account<'info, Vault>,
+                pub owner: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+
+            #[account]
+            #[derive(InitSpace)]
+            pub struct Vault {
+                pub owner: Pubkey,
+                pub bump: u8,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        // The operation initializes the PDA field, paid for by owner
+        let create = normalized.find_instruction("create").unwrap();
+        let Some(InstructionBody::Basic(operations)) = &create.body else {
+            panic!("create should have inferred operations");
+        };
+        let (target, payer) = operations
+            .iter()
+            .find_map(|op| match op {
+                BasicOperation::Initialize { target, payer } => Some((target, payer)),
+                _ => None,
+            })
+            .expect("create should initialize an account");
+        assert_eq!((target.as_str(), payer.as_str()), ("vault", "owner"));
+
+        let account = normalized.find_account_struct("Create").unwrap();
+        let vault = account.find_field(target).unwrap();
+        assert!(vault.inferred_info.is_initialized);
+
+        // Seeds and bump become the field's PDA info
+        let pda = vault
+            .inferred_info
+            .pda
+            .as_ref()
+            .expect("vault should be a PDA");
+        assert_eq!(
+            pda.seeds,
+            [
+                SeedElement::Literal(b"vault".to_vec()),
+                SeedElement::AccountRef("owner".to_string()),
+            ]
+        );
+        assert_eq!(pda.bump_source, BumpSource::Canonical);
+        assert_eq!(pda.program, None);
+
+        // Space resolves through the raw account's InitSpace: 8 + 32 + 1
+        assert_eq!(vault.inferred_info.resolved_space, Some(41));
+
+        // The seed account and the payer are the same field, and must pay
+        let owner = account.find_field(payer).unwrap();
+        assert!(owner.inferred_info.requires_mut);
+    }
+
+    #[test]
+    fn test_associated_token_account_kind() {
+        use anchor_normalizer::model::FieldKind;
+
+        let source = r#"
+            #[program]
+            pub mod wallets {
+                use super::*;
+
+                pub fn open(ctx: Context<Open>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Open<'info> {
+                #[account(
+                    init,
+                    payer = owner,
+                    associated_token::mint = mint,
+                    associated_token::authority = owner,
+                )]
+                pub wallet: Account<'info, TokenAccount>,
+                pub mint: Account<'info, Mint>,
+                #[account(mut)]
+                pub owner: Signer<'info>,
+                pub token_program: Program<'info, Token>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let account = normalized.find_account_struct("Open").unwrap();
+        let kind = |name: &str| account.find_field(name).unwrap().inferred_info.kind.clone();
+
+        assert_eq!(
+            kind("wallet"),
+            FieldKind::AssociatedTokenAccount {
+                mint: "mint".to_string(),
+                authority: "owner".to_string(),
+            }
+        );
+        assert_eq!(kind("mint"), FieldKind::Account);
+        assert_eq!(kind("owner"), FieldKind::Signer);
+        assert_eq!(kind("token_program"), FieldKind::Program);
+        assert!(account
+            .find_field("wallet")
+            .unwrap()
+            .find_constraint("associated_token::mint")
+            .is_some());
+    }
+
+    #[test]
+    fn test_interface_field_kinds() {
+        use anchor_normalizer::model::{FieldKind, ProgramKind};
+
+        let source = r#"
+            #[program]
+            pub mod swaps {
+                use super::*;
+
+                pub fn swap(ctx: Context<Swap>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Swap<'info> {
+                #[account(mut)]
+                pub token: InterfaceAccount<'info, TokenAccount>,
+                pub token_program: Interface<'info, TokenInterface>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let account = normalized.find_account_struct("Swap").unwrap();
+
+        let token = account.find_field("token").unwrap();
+        assert_eq!(token.inferred_info.kind, FieldKind::Account);
+        assert_eq!(token.wrapped_account_type(), Some("TokenAccount"));
+        assert_eq!(
+            token.inferred_info.program_kind,
+            Some(ProgramKind::TokenInterface)
+        );
+
+        let token_program = account.find_field("token_program").unwrap();
+        assert_eq!(token_program.inferred_info.kind, FieldKind::Program);
+        assert_eq!(
+            token_program.inferred_info.program_kind,
+            Some(ProgramKind::TokenInterface)
+        );
+
+        // TokenAccount is a built-in known type, so nothing is flagged
+        assert!(!normalized
+            .validation_issues
+            .iter()
+            .any(|issue| issue.message.contains("unknown account type")));
+    }
+
+    #[test]
+    fn test_constraint_references_instruction_arg() {
+        let source = r#"
+            #[program]
+            pub mod notes {
+                use super::*;
+
+                pub fn create(ctx: Context<Create>, len: u64, extra: u64) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            #[instruction(len: u64)]
+            pub struct Create<'info> {
+                #[account(init, payer = payer, space = 8 + len as usize, seeds = [b"len"], bump)]
+                pub note: Account<'info, Note>,
+                #[account(init, payer = payer, space = 8 + config.log_len as usize)]
+                pub log: Account<'info, Log>,
+                #[account(init, payer = payer, space = 8 + extra as usize)]
+                pub extra_note: Account<'info, Note>,
+                pub config: Account<'info, Config>,
+                #[account(mut)]
+                pub payer: Signer<'info>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let create = normalized.find_account_struct("Create").unwrap();
+        let references = |field: &str, name: &str| {
+            create
+                .find_field(field)
+                .and_then(|f| f.find_constraint(name))
+                .unwrap()
+                .references_instruction_arg(create)
+        };
+
+        assert!(references("note", "space"), "space uses the len argument");
+        assert!(
+            !references("note", "payer"),
+            "payer names a field, not an argument"
+        );
+        assert!(
+            !references("note", "seeds"),
+            "string contents aren't identifiers"
+        );
+        assert!(
+            !references("log", "space"),
+            "space reads the config field, not an argument"
+        );
+        assert!(
+            !references("extra_note", "space"),
+            "extra is a handler parameter missing from #[instruction]"
+        );
+    }
+
+    #[test]
+    fn test_init_order() {
+        let source = |b_seeds: &str, a_seeds: &str| {
+            format!(
+                r#"
+                #[program]
+                pub mod pools {{
+                    use super::*;
+
+                    pub fn create(ctx: Context<Create>) -> Result<()> {{
+                        Ok(())
+                    }}
+                }}
+
+                #[derive(Accounts)]
+                pub struct Create<'info> {{
+                    #[account(init, payer = payer, space = 8, seeds = {b_seeds}, bump)]
+                    pub b: Account<'info, Pool>,
+                    #[account(init, payer = payer, space = 8, seeds = {a_seeds}, bump)]
+                    pub a: Account<'info, Pool>,
+                    #[account(mut)]
+                    pub payer: Signer<'info>,
+                }}
+                "#
+            )
+        };
+
+        // B is declared first but its seeds reference A
+        let program =
+            anchor_parser::parse_str(&source(r#"[b"b", a.key().as_ref()]"#, r#"[b"a"]"#)).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let create = normalized.find_instruction("create").unwrap();
+        assert_eq!(create.init_order(&normalized).unwrap(), vec!["a", "b"]);
+
+        // Seeds referencing each other can't be ordered
+        let program = anchor_parser::parse_str(&source(
+            r#"[b"b", a.key().as_ref()]"#,
+            r#"[b"a", b.key().as_ref()]"#,
+        ))
+        .unwrap();
+        let normalized = normalize(&program).unwrap();
+        let create = normalized.find_instruction("create").unwrap();
+        assert!(create.init_order(&normalized).is_err());
+    }
+
+    #[test]
+    fn test_pda_seed_expressions() {
+        use anchor_normalizer::normalization::seeds::parse_seeds;
+
+        assert_eq!(
+            parse_seeds(r#"[b"a\x01" , "pool" . as_bytes () , & [bump]]"#),
+            vec![
+                SeedElement::Literal(vec![b'a', 1]),
+                SeedElement::Literal(b"pool".to_vec()),
+                SeedElement::Expr("&[bump]".to_string()),
+            ]
+        );
+
+        // A bare `<` is a comparison, so the comma still separates seeds
+        assert_eq!(
+            parse_seeds(r#"[&[(a < b) as u8], b"x,y", Vec::<u8>::new().as_ref()]"#),
+            vec![
+                SeedElement::Expr("&[(a<b)as u8]".to_string()),
+                SeedElement::Literal(b"x,y".to_vec()),
+                SeedElement::Expr("Vec::<u8>::new().as_ref()".to_string()),
+            ]
+        );
+        assert_eq!(parse_seeds("[a < b, c]").len(), 2);
+    }
+
+    #[test]
+    fn test_owner_program() {
+        let source = r#"
+            #[program]
+            pub mod inspector {
+                use super::*;
+
+                pub fn inspect(ctx: Context<Inspect>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Inspect<'info> {
+                #[account(owner = token_program.key())]
+                pub holder: UncheckedAccount<'info>,
+                pub token_program: Program<'info, Token>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let account = normalized.find_account_struct("Inspect").unwrap();
+        let holder = account.find_field("holder").unwrap();
+        assert_eq!(
+            holder.inferred_info.owner_program,
+            Some("token_program.key()".to_string())
+        );
+        let token_program = account.find_field("token_program").unwrap();
+        assert_eq!(token_program.inferred_info.owner_program, None);
+    }
+
+    #[test]
+    fn test_fixed_address() {
+        let source = r#"
+            #[program]
+            pub mod pinned {
+                use super::*;
+
+                pub fn call(ctx: Context<Call>) -> Result<()> {
+                    Ok(())
+                }
+            }
 
-        #[test]
-        fn test_initialize_account_struct() {
-            let program = token_program();
-            let normalized = normalize(&program).unwrap();
+            #[derive(Accounts)]
+            pub struct Call<'info> {
+                #[account(address = crate::ID)]
+                pub this_program: UncheckedAccount<'info>,
+                pub caller: Signer<'info>,
+            }
+        "#;
 
-            let init_account = normalized
-                .find_account_struct("Initialize")
-                .expect("Initialize account struct should exist");
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
 
-            assert_eq!(
-                init_account.fields.len(),
-                3,
-                "Initialize account should have 3 fields"
-            );
+        let account = normalized.find_account_struct("Call").unwrap();
+        assert_eq!(
+            account
+                .find_field("this_program")
+                .unwrap()
+                .inferred_info
+                .fixed_address,
+            Some("crate::ID".to_string())
+        );
+        assert_eq!(
+            account
+                .find_field("caller")
+                .unwrap()
+                .inferred_info
+                .fixed_address,
+            None
+        );
+    }
 
-            let mint_field = init_account
-                .find_field("mint")
-                .expect("mint field should exist in Initialize account");
+    #[test]
+    fn test_raw_field_max_len() {
+        let source = r#"
+            #[program]
+            pub mod profiles {
+                use super::*;
+
+                pub fn create(ctx: Context<Create>) -> Result<()> {
+                    Ok(())
+                }
+            }
 
-            assert_has_constraint(mint_field, "init", None);
-            assert_has_constraint(mint_field, "payer", Some("authority"));
-        }
+            #[derive(Accounts)]
+            pub struct Create {}
+
+            #[account]
+            #[derive(InitSpace)]
+            pub struct Profile {
+                #[max_len(50)]
+                pub name: String,
+                #[max_len(10, 32)]
+                pub tags: Vec<String>,
+                pub owner: Pubkey,
+            }
+        "#;
 
-        #[test]
-        fn test_inferred_operations() {
-            let program = token_program();
-            let normalized = normalize(&program).unwrap();
-            let module = &normalized.modules[0];
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
 
-            // Test initialize has Initialize operation
-            let init_instruction = module
-                .find_instruction("initialize")
-                .expect("initialize instruction should exist");
+        let profile = normalized.find_raw_account("Profile").unwrap();
+        assert_eq!(profile.find_field("name").unwrap().max_len, vec![50]);
+        assert!(profile.find_field("owner").unwrap().max_len.is_empty());
 
-            assert_has_operation(
-                init_instruction,
-                |op| matches!(op, BasicOperation::Initialize { .. }),
-                "initialize instruction should have an Initialize operation",
-            );
+        // Each length sizes one level: 10 strings of up to 32 bytes
+        let tags = profile.find_field("tags").unwrap();
+        assert_eq!(tags.max_len, vec![10, 32]);
+        assert_eq!(tags.max_len_attribute().as_deref(), Some("max_len(10, 32)"));
+        assert_eq!(profile.layout()[1], ("tags", Some(4 + 10 * (4 + 32))));
+    }
 
-            // Test transfer has Transfer operation
-            let transfer_instruction = module
-                .find_instruction("transfer")
-                .expect("transfer instruction should exist");
+    #[test]
+    fn test_external_programs() {
+        let program = example_program("token_vault");
+        let normalized = normalize(&program).unwrap();
 
-            assert_has_operation(
-                transfer_instruction,
-                |op| matches!(op, BasicOperation::Transfer { .. }),
-                "transfer instruction should have a Transfer operation",
-            );
-        }
+        assert_eq!(normalized.external_programs(), vec!["System", "Token"]);
     }
-}
 
-/// Tests for the validation features of the normalizer
-mod validation {
-    use super::*;
-    use anchor_parser::model::{Account, Instruction, Parameter, Program, ProgramModule};
+    #[test]
+    fn test_instruction_uses_sysvars() {
+        let program = example_program("token_vault");
+        let normalized = normalize(&program).unwrap();
+
+        let deposit = normalized.find_instruction("deposit").unwrap();
+        assert_eq!(deposit.uses_sysvars, vec!["Clock"]);
+
+        let initialize = normalized.find_instruction("initialize").unwrap();
+        assert!(initialize.uses_sysvars.is_empty());
+    }
 
     #[test]
-    fn test_duplicate_account_struct() {
-        // Create a program with validation issues
-        let mut program = hello_world_program();
+    fn test_raw_account_discriminator() {
+        let program = example_program("token_vault");
+        let normalized = normalize(&program).unwrap();
 
-        // Add a duplicate account struct
-        let account = Account::new("Initialize", "pub");
-        program.add_account_struct(account);
+        let vault = normalized.find_raw_account("Vault").unwrap();
+        // First 8 bytes of sha256("account:Vault")
+        assert_eq!(vault.discriminator(), [211, 8, 232, 43, 2, 152, 117, 119]);
+        assert_eq!(vault.discriminator_bytes(), vault.discriminator().to_vec());
+    }
 
-        // Normalize it
+    #[test]
+    fn test_raw_account_discriminator_override() {
+        let source = r#"
+            #[program]
+            pub mod custom {
+                use super::*;
+
+                pub fn touch(ctx: Context<Touch>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Touch {}
+
+            #[account(discriminator = [9, 9, 9, 9])]
+            pub struct Custom {
+                pub value: u64,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
         let normalized = normalize(&program).unwrap();
 
-        // Check for validation issues
-        assert!(
-            !normalized.validation_issues.is_empty(),
-            "Should have validation issues with duplicate account struct"
+        let custom = normalized.find_raw_account("Custom").unwrap();
+        assert_eq!(custom.discriminator_override, Some(vec![9, 9, 9, 9]));
+        assert_eq!(custom.discriminator_bytes(), vec![9, 9, 9, 9]);
+        assert_ne!(
+            custom.discriminator().to_vec(),
+            custom.discriminator_bytes()
         );
-        assert_validation_issue(&normalized, "Duplicate account struct name");
     }
 
     #[test]
-    fn test_missing_account_struct() {
-        // Create a fresh program with a non-existent account struct reference
-        let mut program = Program::new();
-
-        // Add a program module
-        let mut module = ProgramModule::new("test_program", "pub");
+    fn test_init_space() {
+        let source = r#"
+            #[program]
+            pub mod profiles {
+                use super::*;
+
+                pub fn create(ctx: Context<Create>) -> Result<()> {
+                    Ok(())
+                }
+            }
 
-        // Add an instruction that references a non-existent account struct
-        let instruction = Instruction::new("initialize", "pub")
-            .with_parameter(Parameter::new_context("ctx", "NonExistentStruct"))
-            .with_return_type("Result<()>")
-            .with_context_type("NonExistentStruct");
+            #[derive(Accounts)]
+            pub struct Create<'info> {
+                #[account(init, payer = owner, space = 8 + Profile::INIT_SPACE)]
+                pub profile: Account<'info, Profile>,
+                #[account(mut)]
+                pub owner: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
 
-        module.add_instruction(instruction);
-        program.add_program_module(module);
+            #[account]
+            #[derive(InitSpace)]
+            pub struct Profile {
+                #[max_len(50)]
+                pub name: String,
+                pub owner: Pubkey,
+                pub score: Option<u64>,
+                pub bump: u8,
+            }
+        "#;
 
-        // Normalize it
+        let program = anchor_parser::parse_str(source).unwrap();
         let normalized = normalize(&program).unwrap();
 
-        // Print all validation issues to help debug
-        println!("Validation issues: {:?}", normalized.validation_issues);
+        let profile = normalized.find_raw_account("Profile").unwrap();
+        assert!(profile.derives_init_space);
+        // (4 + 50) + 32 + (1 + 8) + 1
+        assert_eq!(profile.init_space(), Some(96));
 
-        // Check for validation issues - look for "undefined account struct" instead
-        assert_validation_issue(&normalized, "undefined account struct");
+        let field = normalized
+            .find_account_struct("Create")
+            .and_then(|a| a.find_field("profile"))
+            .unwrap();
+        assert_eq!(field.inferred_info.resolved_space, Some(8 + 96));
     }
-}
 
-/// Tests for the inference features of the normalizer
-mod inference {
-    use super::*;
+    #[test]
+    fn test_size_of_space() {
+        let source = r#"
+            #[program]
+            pub mod vaults {
+                use super::*;
+
+                pub fn create(ctx: Context<Create>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Create<'info> {
+                #[account(init, payer = owner, space = 8 + std::mem::size_of::<Vault>())]
+                pub vault: Account<'info, Vault>,
+                #[account(mut)]
+                pub owner: Signer<'info>,
+                pub system_program: Program<'info, System>,
+            }
+
+            #[account]
+            pub struct Vault {
+                pub owner: Pubkey,
+                pub bump: u8,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+
+        let vault = normalized.find_raw_account("Vault").unwrap();
+        assert_eq!(vault.layout(), vec![("owner", Some(32)), ("bump", Some(1))]);
+        assert_eq!(vault.layout_size(), Some(33));
+
+        let field = normalized
+            .find_account_struct("Create")
+            .and_then(|a| a.find_field("vault"))
+            .unwrap();
+        assert_eq!(field.inferred_info.resolved_space, Some(41));
+    }
 
     #[test]
-    fn test_mut_inferred_from_init() {
-        let program = token_program();
+    fn test_init_space_requires_derive() {
+        // token_vault defines INIT_SPACE by hand instead of deriving it
+        let program = example_program("token_vault");
         let normalized = normalize(&program).unwrap();
 
-        let init_account = normalized
-            .find_account_struct("Initialize")
-            .expect("Initialize account struct should exist");
+        let vault = normalized.find_raw_account("Vault").unwrap();
+        assert!(!vault.derives_init_space);
+        assert_eq!(vault.init_space(), None);
+    }
 
-        let mint_field = init_account
-            .find_field("mint")
-            .expect("mint field should exist");
+    #[test]
+    fn test_custom_inference_rule() {
+        use anchor_normalizer::error::Result;
+        use anchor_normalizer::model::{
+            NormalizedAccountField, NormalizedAccountStruct, NormalizedConstraint,
+            NormalizedProgram,
+        };
+        use anchor_normalizer::normalization::inference::{
+            default_rules, run_inference, InferenceRule,
+        };
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        struct CountingRule(Rc<Cell<usize>>);
+
+        impl InferenceRule for CountingRule {
+            fn apply(&self, _program: &mut NormalizedProgram) -> Result<()> {
+                self.0.set(self.0.get() + 1);
+                Ok(())
+            }
+        }
 
-        // The field should have both init and mut constraints
-        assert_has_constraint(mint_field, "init", None);
-        assert!(
-            mint_field.inferred_info.is_initialized,
-            "Field should be marked as initialized"
-        );
+        let mut program = NormalizedProgram::new("program:test", "test");
+        let mut account = NormalizedAccountStruct::new("Initialize", "pub");
+        let mut field = NormalizedAccountField::new("mint", "Account<'info, Mint>");
+        field.add_constraint(NormalizedConstraint::without_value("init", false));
+        account.add_field(field);
+        account.add_field(NormalizedAccountField::new(
+            "system_program",
+            "Program<'info, System>",
+        ));
+        program.add_account_struct(account);
 
-        // Check if mut was either present or inferred
-        let has_mut_constraint = mint_field
-            .constraints
-            .iter()
-            .any(|c| c.constraint_type == "mut");
+        let calls = Rc::new(Cell::new(0));
+        let mut rules = default_rules();
+        rules.push(Box::new(CountingRule(calls.clone())));
+        run_inference(&mut program, &rules).unwrap();
+
+        assert_eq!(calls.get(), 1, "Custom rule should run once");
 
+        let account = program.find_account_struct("Initialize").unwrap();
+        let mint = account.find_field("mint").unwrap();
         assert!(
-            has_mut_constraint || mint_field.inferred_info.requires_mut,
-            "Field should have explicit or inferred mut constraint"
+            mint.constraints
+                .iter()
+                .any(|c| c.constraint_type == "mut" && c.is_inferred),
+            "Default rules should still infer mut from init"
+        );
+        assert_eq!(
+            account
+                .find_field("system_program")
+                .unwrap()
+                .inferred_info
+                .program_kind,
+            Some(ProgramKind::System)
         );
     }
 
@@ -292,12 +2657,103 @@ mod inference {
             "system_program should be detected as a program-related account"
         );
     }
+
+    #[test]
+    fn test_token_2022_program_kind() {
+        let source = r#"
+            #[program]
+            pub mod swaps {
+                use super::*;
+
+                pub fn swap(ctx: Context<Swap>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Swap<'info> {
+                #[account(mut)]
+                pub token: InterfaceAccount<'info, TokenAccount>,
+                pub token_program: Program<'info, Token2022>,
+                pub legacy_program: Program<'info, anchor_spl::token::Token>,
+                pub namespaced_program: Program<'info, anchor_spl::token_2022::Token>,
+                pub interface_program: Interface<'info, token_interface::TokenInterface>,
+                pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+                pub data: Account<'info, TokenAccount>,
+            }
+        "#;
+
+        let program = anchor_parser::parse_str(source).unwrap();
+        let normalized = normalize(&program).unwrap();
+        let account = normalized.find_account_struct("Swap").unwrap();
+
+        let kind = |name: &str| {
+            account
+                .find_field(name)
+                .unwrap()
+                .inferred_info
+                .program_kind
+                .clone()
+        };
+        assert_eq!(kind("token_program"), Some(ProgramKind::Token2022));
+        assert_eq!(kind("legacy_program"), Some(ProgramKind::Token));
+        assert_eq!(kind("namespaced_program"), Some(ProgramKind::Token2022));
+        assert_eq!(kind("interface_program"), Some(ProgramKind::TokenInterface));
+        assert_eq!(kind("token"), Some(ProgramKind::TokenInterface));
+        assert_eq!(kind("mint"), Some(ProgramKind::TokenInterface));
+        assert_eq!(kind("data"), None);
+        assert!(normalized
+            .external_programs()
+            .contains(&"Token2022".to_string()));
+    }
+
+    #[test]
+    fn test_program_kind_detection() {
+        let program = token_program();
+        let normalized = normalize(&program).unwrap();
+
+        let init_account = normalized
+            .find_account_struct("Initialize")
+            .expect("Initialize account struct should exist");
+
+        let sys_program_field = init_account
+            .find_field("system_program")
+            .expect("system_program field should exist");
+        assert_eq!(
+            sys_program_field.inferred_info.program_kind,
+            Some(ProgramKind::System)
+        );
+
+        // Non-program fields are not classified
+        let mint_field = init_account.find_field("mint").unwrap();
+        assert_eq!(mint_field.inferred_info.program_kind, None);
+    }
 }
 
 /// Tests for error handling in the normalizer
 mod error_handling {
     use super::*;
 
+    #[test]
+    fn test_empty_program_module() {
+        let program = anchor_parser::parse_str("#[program] pub mod empty_program {}").unwrap();
+        let normalized = normalize(&program).expect("Empty module should still normalize");
+
+        assert_eq!(normalized.name, "empty_program");
+        assert_eq!(normalized.modules.len(), 1);
+        assert!(normalized.modules[0].instructions.is_empty());
+
+        let issue = normalized
+            .validation_issues
+            .iter()
+            .find(|issue| issue.message == "Program module empty_program has no instructions")
+            .expect("Should report the empty module");
+        assert_eq!(
+            issue.severity,
+            anchor_normalizer::model::IssueSeverity::Info
+        );
+    }
+
     #[test]
     fn test_empty_program() {
         // Create a program with no program modules
@@ -326,6 +2782,23 @@ mod error_handling {
         }
     }
 
+    #[test]
+    fn test_default_program_name() {
+        // No modules and no source path leave nothing to name the program after
+        let program = create_invalid_program(false, true);
+        assert!(program.source_path.is_none());
+
+        let config = NormalizeConfig::new().with_default_program_name("fallback");
+        let normalized = normalize_with_config(&program, &config)
+            .expect("Default name should be used instead of an error");
+        assert_eq!(normalized.name, "fallback");
+
+        // A module name still takes precedence
+        let named = create_invalid_program(true, true);
+        let normalized = normalize_with_config(&named, &config).unwrap();
+        assert_eq!(normalized.name, "invalid_program");
+    }
+
     // TODO: When instruction validation is implemented, update this test
     // to verify that instructions without context parameters are flagged.
     #[test]
@@ -352,7 +2825,7 @@ mod error_handling {
         // The current implementation doesn't validate that instructions have context parameters,
         // so we'll check for something we know is validated (structure)
         assert!(
-            normalized.modules.len() > 0,
+            !normalized.modules.is_empty(),
             "Should have at least one module"
         );
 
@@ -397,3 +2870,304 @@ mod error_handling {
         }
     }
 }
+
+/// Exporters render normalized programs to other formats
+mod export {
+    use super::*;
+    use anchor_normalizer::export::{
+        to_anchor_source, to_constraint_matrix_csv, to_constraint_matrix_markdown,
+        to_markdown_report, to_typescript,
+    };
+    use anchor_normalizer::model::{BumpSource, NormalizedProgram};
+
+    #[test]
+    fn test_typescript_raw_account() {
+        let program = example_program("token_vault");
+        let normalized = normalize(&program).unwrap();
+
+        let ts = to_typescript(&normalized);
+        assert!(ts.contains("export interface Vault {"));
+        assert!(ts.contains("authority: PublicKey;"));
+        assert!(ts.contains("bump: number;"));
+        assert!(ts.contains("export interface DepositArgs {"));
+        assert!(ts.contains("amount: BN;"));
+    }
+
+    #[test]
+    fn test_constraint_matrix_csv() {
+        let normalized = normalize(&token_program()).unwrap();
+        let csv = to_constraint_matrix_csv(&normalized);
+        let mut lines = csv.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("account,field,mut,signer,init,close,has_one,seeds")
+        );
+
+        let authority = csv
+            .lines()
+            .find(|line| line.starts_with("Initialize,authority,"))
+            .expect("authority row should be present");
+        let cells: Vec<&str> = authority.split(',').collect();
+        // Columns: account, field, mut, signer, ...
+        assert_eq!(cells[3], "true", "authority should show signer=true");
+
+        let mint = csv
+            .lines()
+            .find(|line| line.starts_with("Initialize,mint,"))
+            .unwrap();
+        assert_eq!(mint, "Initialize,mint,true,false,true,false,false,false");
+
+        let markdown = to_constraint_matrix_markdown(&normalized);
+        assert!(markdown.contains("### Initialize"));
+        assert!(markdown.contains("| field | mut | signer | init | close | has_one | seeds |"));
+    }
+
+    #[test]
+    fn test_markdown_report_account_tables() {
+        let normalized = normalize(&token_program()).unwrap();
+        let report = to_markdown_report(&normalized);
+
+        let transfer = report
+            .split("### ")
+            .find(|section| section.starts_with("transfer\n"))
+            .expect("transfer section should be present");
+        assert!(transfer.contains("Arguments: `amount: u64`"));
+        assert!(transfer.contains("| account | mut | signer | init |"));
+        assert!(transfer.contains("| authority |   | x |   |"));
+        assert!(transfer.contains("| from | x |   |   |"));
+        assert!(transfer.contains("| to | x |   |   |"));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_round_trip() {
+        let normalized = normalize(&token_program()).unwrap();
+
+        let bytes = normalized.to_bincode().unwrap();
+        let decoded = NormalizedProgram::from_bincode(&bytes).unwrap();
+        assert_eq!(decoded, normalized);
+
+        let json = serde_json::to_vec(&normalized).unwrap();
+        assert!(bytes.len() < json.len() / 2, "bincode should be compact");
+
+        assert!(NormalizedProgram::from_bincode(&bytes[..bytes.len() / 2]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(NormalizedProgram::from_bincode(&trailing).is_err());
+    }
+
+    #[test]
+    fn test_anchor_source_keeps_item_order() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[account]
+            pub struct Counter {
+                pub count: u64,
+            }
+
+            #[derive(Accounts)]
+            pub struct Bump<'info> {
+                #[account(mut)]
+                pub counter: Account<'info, Counter>,
+            }
+
+            #[program]
+            pub mod counter {
+                use super::*;
+
+                pub fn bump(ctx: Context<Bump>) -> Result<()> {
+                    Ok(())
+                }
+            }
+        "#;
+
+        let original = normalize(&anchor_parser::parse_str(source).unwrap()).unwrap();
+        let regenerated = to_anchor_source(&original);
+
+        let position = |needle: &str| {
+            regenerated
+                .find(needle)
+                .unwrap_or_else(|| panic!("{} should be emitted", needle))
+        };
+        assert!(position("pub struct Counter") < position("pub struct Bump"));
+        assert!(position("pub struct Bump") < position("pub mod counter"));
+
+        // The order survives parsing the regenerated source again
+        let reparsed = normalize(&anchor_parser::parse_str(&regenerated).unwrap()).unwrap();
+        assert_eq!(reparsed.item_order, original.item_order);
+        assert_eq!(to_anchor_source(&reparsed), regenerated);
+    }
+
+    #[test]
+    fn test_anchor_source_error_enum() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[error_code]
+            pub enum VaultError {
+                #[msg("Vault is \"locked\"")]
+                Locked,
+                Empty,
+            }
+
+            #[derive(Accounts)]
+            pub struct Withdraw<'info> {
+                #[account(mut @ VaultError::Locked)]
+                pub vault: Signer<'info>,
+            }
+        "#;
+
+        let original = normalize(&anchor_parser::parse_str(source).unwrap()).unwrap();
+        let error_enum = original.find_error_enum("VaultError").unwrap();
+        assert_eq!(error_enum.message("Locked"), Some(r#"Vault is "locked""#));
+        assert_eq!(error_enum.message("Empty"), None);
+
+        let regenerated = to_anchor_source(&original);
+        assert!(regenerated.contains(
+            r#"    #[msg("Vault is \"locked\"")]
+    Locked,
+    Empty,"#
+        ));
+        assert!(regenerated.find("pub enum VaultError") < regenerated.find("pub struct Withdraw"));
+
+        let reparsed = normalize(&anchor_parser::parse_str(&regenerated).unwrap()).unwrap();
+        assert_eq!(reparsed.error_enums, original.error_enums);
+        assert_eq!(reparsed.item_order, original.item_order);
+    }
+
+    #[test]
+    fn test_anchor_source_where_clause() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[program]
+            pub mod store {
+                use super::*;
+
+                pub fn close(ctx: Context<Close>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Close<'info, T>
+            where
+                T: Owner + Clone,
+            {
+                pub data: Account<'info, T>,
+            }
+        "#;
+
+        let original = normalize(&anchor_parser::parse_str(source).unwrap()).unwrap();
+        let account = original.find_account_struct("Close").unwrap();
+        assert_eq!(
+            account.where_clause.as_deref(),
+            Some("where T: Owner + Clone,")
+        );
+
+        let regenerated = to_anchor_source(&original);
+        assert!(regenerated.contains("pub struct Close<'info> where T: Owner + Clone, {"));
+
+        let reparsed = normalize(&anchor_parser::parse_str(&regenerated).unwrap()).unwrap();
+        assert_eq!(reparsed.account_structs, original.account_structs);
+
+        let rebuilt = anchor_parser::Program::from(&original);
+        assert_eq!(
+            rebuilt.account_structs[0].where_clause.as_deref(),
+            Some("where T: Owner + Clone,")
+        );
+    }
+
+    #[test]
+    fn test_anchor_source_pda_round_trip() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            #[program]
+            pub mod escrow {
+                use super::*;
+
+                pub fn open(ctx: Context<Open>, id: u64) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Open<'info> {
+                #[account(
+                    init,
+                    payer = maker,
+                    space = 8 + 8,
+                    seeds = [b"escrow", maker.key().as_ref(), state.seed.as_ref()],
+                    bump,
+                )]
+                pub escrow: Account<'info, Escrow>,
+                #[account(
+                    seeds = [b"state"],
+                    bump = state.bump,
+                    seeds::program = other_program.key(),
+                )]
+                pub state: Account<'info, Escrow>,
+                #[account(mut)]
+                pub maker: Signer<'info>,
+                pub other_program: Program<'info, System>,
+                pub system_program: Program<'info, System>,
+            }
+
+            #[account]
+            pub struct Escrow {
+                pub seed: u64,
+                pub bump: u8,
+            }
+        "#;
+
+        let original = normalize(&anchor_parser::parse_str(source).unwrap()).unwrap();
+        let regenerated = to_anchor_source(&original);
+
+        let reparsed = anchor_parser::parse_str(&regenerated)
+            .unwrap_or_else(|e| panic!("Regenerated source should parse: {}", e));
+        let round_tripped = normalize(&reparsed).unwrap();
+
+        let pda = |program: &NormalizedProgram, name: &str| {
+            program
+                .find_account_struct("Open")
+                .and_then(|a| a.find_field(name))
+                .and_then(|f| f.inferred_info.pda.clone())
+                .unwrap_or_else(|| panic!("{} should be a PDA", name))
+        };
+
+        let escrow = pda(&round_tripped, "escrow");
+        assert_eq!(escrow, pda(&original, "escrow"));
+        assert_eq!(
+            escrow.seeds,
+            vec![
+                SeedElement::Literal(b"escrow".to_vec()),
+                SeedElement::AccountRef("maker".to_string()),
+                SeedElement::Expr("state.seed.as_ref()".to_string()),
+            ]
+        );
+        assert_eq!(escrow.bump_source, BumpSource::Canonical);
+
+        let state = pda(&round_tripped, "state");
+        assert_eq!(state, pda(&original, "state"));
+        assert_eq!(
+            state.bump_source,
+            BumpSource::Stored("state.bump".to_string())
+        );
+        assert_eq!(state.program.as_deref(), Some("other_program.key()"));
+
+        // Inferred constraints stay out of the regenerated source
+        assert!(!regenerated.contains("signer"));
+    }
+}
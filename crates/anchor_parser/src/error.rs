@@ -13,6 +13,9 @@ pub enum ParseError {
 
     /// Other parse error
     Parse(String),
+
+    /// Input exceeded a configured parse limit
+    LimitExceeded(String),
 }
 
 impl fmt::Display for ParseError {
@@ -21,6 +24,7 @@ impl fmt::Display for ParseError {
             ParseError::Io(err) => write!(f, "I/O error: {}", err),
             ParseError::Syntax(err) => write!(f, "Syntax error: {}", err),
             ParseError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            ParseError::LimitExceeded(msg) => write!(f, "Parse limit exceeded: {}", msg),
         }
     }
 }
@@ -30,7 +34,7 @@ impl std::error::Error for ParseError {
         match self {
             ParseError::Io(err) => Some(err),
             ParseError::Syntax(err) => Some(err),
-            ParseError::Parse(_) => None,
+            ParseError::Parse(_) | ParseError::LimitExceeded(_) => None,
         }
     }
 }
@@ -2,11 +2,22 @@
 //!
 //! This crate provides functionality to parse Anchor program source code
 //! and convert it into a semantic model.
+//!
+//! The model types only need `alloc`. Parsing and file I/O live behind the
+//! default `std` feature; disable it to use the model under `no_std`.
+//! `Serialize` derives live behind the default `serde` feature.
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "std")]
 pub mod error;
 pub mod model;
+#[cfg(feature = "std")]
 pub mod parser;
 
+#[cfg(feature = "std")]
 pub use error::{ParseError, Result};
 pub use model::program::Program;
 
@@ -16,4 +27,8 @@ pub mod ast {
 }
 
 // Functions to parse programs
-pub use parser::{parse_file, parse_str};
+#[cfg(feature = "std")]
+pub use parser::{
+    parse_directory, parse_file, parse_file_with_limits, parse_str, parse_str_timed,
+    parse_str_with, parse_str_with_limits, ParseFeatures, ParseLimits, ParseMetrics,
+};
@@ -4,9 +4,14 @@
 //! account validation structures (#[derive(Accounts)]) and raw account structures (#[account]).
 
 /// Represents an account structure with #[derive(Accounts)]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "serde")]
 use serde::Serialize;
 
-#[derive(Debug, Clone, Default, Serialize)]
+use crate::model::instruction::Parameter;
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Account {
     /// Name of the account struct
     pub name: String,
@@ -16,10 +21,17 @@ pub struct Account {
 
     /// Fields in the account struct
     pub fields: Vec<AccountField>,
+
+    /// Instruction arguments declared with `#[instruction(...)]`
+    pub instruction_args: Vec<Parameter>,
+
+    /// The struct's `where` clause as written (e.g., "where T: Owner")
+    pub where_clause: Option<String>,
 }
 
 /// Represents a field in an account structure
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct AccountField {
     /// Name of the field
     pub name: String,
@@ -29,10 +41,14 @@ pub struct AccountField {
 
     /// Constraints on the field (from #[account(...)])
     pub constraints: Vec<Constraint>,
+
+    /// Doc comment on the field, one line per `///` line
+    pub documentation: Option<String>,
 }
 
 /// Represents a constraint on an account field
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Constraint {
     /// Type of constraint (init, payer, seeds, etc.)
     pub constraint_type: String,
@@ -42,7 +58,8 @@ pub struct Constraint {
 }
 
 /// Represents a raw account with #[account]
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct RawAccount {
     /// Name of the account struct
     pub name: String,
@@ -52,10 +69,17 @@ pub struct RawAccount {
 
     /// Fields in the account struct
     pub fields: Vec<RawAccountField>,
+
+    /// Whether the struct has #[derive(InitSpace)]
+    pub derives_init_space: bool,
+
+    /// Explicit discriminator from `#[account(discriminator = ...)]`
+    pub discriminator_override: Option<Vec<u8>>,
 }
 
 /// Represents a field in a raw account
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct RawAccountField {
     /// Name of the field
     pub name: String,
@@ -65,6 +89,13 @@ pub struct RawAccountField {
 
     /// Visibility of the field
     pub visibility: String,
+
+    /// Non-doc attributes on the field (e.g., "max_len(50)")
+    pub attributes: Vec<String>,
+
+    /// Lengths from `#[max_len(N, ...)]`, outermost first, used to size
+    /// `Vec`/`String` fields (`#[max_len(10, 32)]` on a `Vec<String>`)
+    pub max_len: Vec<usize>,
 }
 
 impl Account {
@@ -74,6 +105,8 @@ impl Account {
             name: name.into(),
             visibility: visibility.into(),
             fields: Vec::new(),
+            instruction_args: Vec::new(),
+            where_clause: None,
         }
     }
 
@@ -82,6 +115,11 @@ impl Account {
         self.fields.push(field);
     }
 
+    /// Add an `#[instruction(...)]` argument to the account struct
+    pub fn add_instruction_arg(&mut self, arg: Parameter) {
+        self.instruction_args.push(arg);
+    }
+
     /// Find a field by name
     pub fn find_field(&self, name: &str) -> Option<&AccountField> {
         self.fields.iter().find(|f| f.name == name)
@@ -107,6 +145,7 @@ impl AccountField {
             name: name.into(),
             ty: ty.into(),
             constraints: Vec::new(),
+            documentation: None,
         }
     }
 
@@ -122,6 +161,12 @@ impl AccountField {
             .find(|c| c.constraint_type == constraint_type)
     }
 
+    /// Builder method: with documentation
+    pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
+        self.documentation = Some(docs.into());
+        self
+    }
+
     /// Builder method: add a constraint and return self
     pub fn with_constraint(mut self, constraint: Constraint) -> Self {
         self.add_constraint(constraint);
@@ -168,6 +213,8 @@ impl RawAccount {
             name: name.into(),
             visibility: visibility.into(),
             fields: Vec::new(),
+            derives_init_space: false,
+            discriminator_override: None,
         }
     }
 
@@ -205,8 +252,21 @@ impl RawAccountField {
             name: name.into(),
             ty: ty.into(),
             visibility: visibility.into(),
+            attributes: Vec::new(),
+            max_len: Vec::new(),
         }
     }
+
+    /// Add an attribute to the field
+    pub fn add_attribute(&mut self, attribute: impl Into<String>) {
+        self.attributes.push(attribute.into());
+    }
+
+    /// Builder method: with maximum lengths, outermost first
+    pub fn with_max_len(mut self, max_len: Vec<usize>) -> Self {
+        self.max_len = max_len;
+        self
+    }
 }
 
 #[cfg(all(test, feature = "unit_test"))]
@@ -4,9 +4,12 @@
 //! They define the entry points and behavior of a Solana program.
 
 /// Represents an instruction in an Anchor program
+use alloc::{format, string::String, vec::Vec};
+#[cfg(feature = "serde")]
 use serde::Serialize;
 
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Instruction {
     /// Name of the instruction
     pub name: String,
@@ -20,12 +23,25 @@ pub struct Instruction {
     /// Return type (if any)
     pub return_type: Option<String>,
 
+    /// Success type of a `Result<T>` return type (e.g., "()" or "u64")
+    pub ok_type: Option<String>,
+
     /// Type of the context parameter (e.g., "Initialize")
     pub context_type: Option<String>,
+
+    /// Non-doc attributes on the function (e.g., "interface", "access_control(check(&ctx))")
+    pub attributes: Vec<String>,
+
+    /// Sysvars referenced in the function body (e.g., "Clock" from `Clock::get()`)
+    pub sysvars: Vec<String>,
+
+    /// Functions called by name in the function body (e.g., "helper" from `helper(ctx)?`)
+    pub calls: Vec<String>,
 }
 
 /// Represents a parameter to an instruction
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Parameter {
     /// Name of the parameter
     pub name: String,
@@ -35,6 +51,9 @@ pub struct Parameter {
 
     /// Whether this is a Context parameter
     pub is_context: bool,
+
+    /// Documentation from `///` comments on the parameter (if any)
+    pub documentation: Option<String>,
 }
 
 impl Instruction {
@@ -45,7 +64,11 @@ impl Instruction {
             visibility: visibility.into(),
             parameters: Vec::new(),
             return_type: None,
+            ok_type: None,
             context_type: None,
+            attributes: Vec::new(),
+            sysvars: Vec::new(),
+            calls: Vec::new(),
         }
     }
 
@@ -59,11 +82,50 @@ impl Instruction {
         self.return_type = Some(ty.into());
     }
 
+    /// Set the success type of the instruction's Result return type
+    pub fn set_ok_type(&mut self, ty: impl Into<String>) {
+        self.ok_type = Some(ty.into());
+    }
+
     /// Set the context type of the instruction
     pub fn set_context_type(&mut self, ty: impl Into<String>) {
         self.context_type = Some(ty.into());
     }
 
+    /// Add an attribute to the instruction
+    pub fn add_attribute(&mut self, attribute: impl Into<String>) {
+        self.attributes.push(attribute.into());
+    }
+
+    /// Record a sysvar referenced by the instruction body, ignoring repeats
+    pub fn add_sysvar(&mut self, sysvar: impl Into<String>) {
+        let sysvar = sysvar.into();
+        if !self.sysvars.contains(&sysvar) {
+            self.sysvars.push(sysvar);
+        }
+    }
+
+    /// Record a function called by the instruction body, ignoring repeats
+    pub fn add_call(&mut self, function: impl Into<String>) {
+        let function = function.into();
+        if !self.calls.contains(&function) {
+            self.calls.push(function);
+        }
+    }
+
+    /// Check if the instruction carries an attribute with the given name
+    ///
+    /// Matches both bare attributes (`interface`) and attributes with
+    /// arguments (`access_control(...)`).
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes.iter().any(|attr| {
+            attr == name
+                || attr
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('(') || rest.starts_with(' '))
+        })
+    }
+
     /// Find a parameter by name
     pub fn find_parameter(&self, name: &str) -> Option<&Parameter> {
         self.parameters.iter().find(|p| p.name == name)
@@ -97,6 +159,12 @@ impl Instruction {
         self.context_type = Some(ty.into());
         self
     }
+
+    /// Builder method: with attribute
+    pub fn with_attribute(mut self, attribute: impl Into<String>) -> Self {
+        self.add_attribute(attribute);
+        self
+    }
 }
 
 impl Parameter {
@@ -106,6 +174,7 @@ impl Parameter {
             name: name.into(),
             ty: ty.into(),
             is_context,
+            documentation: None,
         }
     }
 
@@ -116,17 +185,14 @@ impl Parameter {
             name: name.into(),
             ty: format!("Context<{}>", context_type),
             is_context: true,
+            documentation: None,
         }
     }
-}
 
-impl Default for Parameter {
-    fn default() -> Self {
-        Self {
-            name: String::new(),
-            ty: String::new(),
-            is_context: false,
-        }
+    /// Builder method: with documentation
+    pub fn with_documentation(mut self, docs: impl Into<String>) -> Self {
+        self.documentation = Some(docs.into());
+        self
     }
 }
 
@@ -237,6 +303,18 @@ mod tests {
         assert_eq!(instruction.parameters[1].name, "amount");
     }
 
+    #[test]
+    fn test_instruction_has_attribute() {
+        let instruction = Instruction::new("transfer_hook", "pub")
+            .with_attribute("interface(spl_transfer_hook_interface::execute)")
+            .with_attribute("inline");
+
+        assert!(instruction.has_attribute("interface"));
+        assert!(instruction.has_attribute("inline"));
+        assert!(!instruction.has_attribute("inter"));
+        assert!(!instruction.has_attribute("access_control"));
+    }
+
     #[test]
     fn test_parameter_new() {
         let param = Parameter::new("amount", "u64", false);
@@ -11,7 +11,9 @@ pub mod program;
 // Re-export all types from submodules for easier access
 pub use account::{Account, AccountField, Constraint, RawAccount, RawAccountField};
 pub use instruction::{Instruction, Parameter};
-pub use program::{Program, ProgramModule};
+pub use program::{
+    CfgPredicate, ErrorEnum, Import, ItemKind, ItemRef, Program, ProgramCounts, ProgramModule,
+};
 
 #[cfg(all(test, feature = "unit_test"))]
 mod tests {
@@ -3,6 +3,9 @@
 //! This module defines the core structures that represent an Anchor program,
 //! including program modules, instructions, and account structures.
 
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt;
+#[cfg(feature = "serde")]
 use serde::Serialize;
 
 use crate::model::account::{Account, RawAccount};
@@ -12,7 +15,8 @@ use crate::model::instruction::Instruction;
 ///
 /// In Anchor, a module marked with #[program] contains instruction handlers
 /// that define the behavior of the Solana program.
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ProgramModule {
     /// Name of the program module
     pub name: String,
@@ -22,13 +26,190 @@ pub struct ProgramModule {
 
     /// Instructions defined in the program
     pub instructions: Vec<Instruction>,
+
+    /// Use statements declared inside the module (e.g., "super::*")
+    pub uses: Vec<String>,
+
+    /// Predicates from `#[cfg(...)]` attributes on the module, all of
+    /// which must hold for the module to be compiled
+    pub cfg: Vec<CfgPredicate>,
+}
+
+/// A parsed `#[cfg(...)]` predicate
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CfgPredicate {
+    /// A bare option (`test`) or a key-value option (`feature = "devnet"`)
+    Option { name: String, value: Option<String> },
+
+    /// `all(...)`, true when every predicate holds
+    All(Vec<CfgPredicate>),
+
+    /// `any(...)`, true when at least one predicate holds
+    Any(Vec<CfgPredicate>),
+
+    /// `not(...)`
+    Not(Box<CfgPredicate>),
+}
+
+impl CfgPredicate {
+    /// Evaluate the predicate, asking `is_set` whether each option is enabled
+    pub fn evaluate(&self, is_set: &impl Fn(&str, Option<&str>) -> bool) -> bool {
+        match self {
+            CfgPredicate::Option { name, value } => is_set(name, value.as_deref()),
+            CfgPredicate::All(predicates) => predicates.iter().all(|p| p.evaluate(is_set)),
+            CfgPredicate::Any(predicates) => predicates.iter().any(|p| p.evaluate(is_set)),
+            CfgPredicate::Not(predicate) => !predicate.evaluate(is_set),
+        }
+    }
+}
+
+/// Kind of a top-level item recorded in [`Program::item_order`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ItemKind {
+    /// A #[program] module
+    ProgramModule,
+
+    /// A #[derive(Accounts)] struct
+    AccountStruct,
+
+    /// A #[account] struct
+    RawAccount,
+
+    /// A #[error_code] enum
+    ErrorEnum,
+}
+
+/// Reference to a top-level item by kind and name
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ItemRef {
+    /// Kind of item
+    pub kind: ItemKind,
+
+    /// Item name
+    pub name: String,
+}
+
+impl ItemRef {
+    /// Create a new item reference
+    pub fn new(kind: ItemKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+        }
+    }
+}
+
+/// A top-level use declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Import {
+    /// The imported path (e.g., "crate::state::Vault")
+    pub path: String,
+
+    /// Whether the declaration re-exports the path (`pub use`)
+    pub is_reexport: bool,
+}
+
+impl Import {
+    /// Create a new import
+    pub fn new(path: impl Into<String>, is_reexport: bool) -> Self {
+        Self {
+            path: path.into(),
+            is_reexport,
+        }
+    }
+}
+
+/// An enum with the #[error_code] attribute
+///
+/// Its variants are the custom errors constraints can map to with
+/// `@ Enum::Variant`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ErrorEnum {
+    /// Name of the enum
+    pub name: String,
+
+    /// Variant names in declaration order
+    pub variants: Vec<String>,
+
+    /// Message of each variant from `#[msg("...")]`, aligned with `variants`
+    pub messages: Vec<Option<String>>,
+}
+
+impl ErrorEnum {
+    /// Create a new error enum with no variants
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variants: Vec::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Add a variant without a message to the enum
+    pub fn add_variant(&mut self, variant: impl Into<String>) {
+        self.variants.push(variant.into());
+        self.messages.push(None);
+    }
+
+    /// Add a variant with its `#[msg("...")]` message to the enum
+    pub fn add_variant_with_message(
+        &mut self,
+        variant: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.variants.push(variant.into());
+        self.messages.push(Some(message.into()));
+    }
+
+    /// Get the message of a variant, if it has one
+    pub fn message(&self, variant: &str) -> Option<&str> {
+        let index = self.variants.iter().position(|v| v == variant)?;
+        self.messages.get(index)?.as_deref()
+    }
+
+    /// Check whether the enum has a variant with the given name
+    pub fn has_variant(&self, name: &str) -> bool {
+        self.variants.iter().any(|v| v == name)
+    }
+}
+
+/// Number of items of each kind in a [`Program`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgramCounts {
+    /// Program modules
+    pub program_modules: usize,
+
+    /// Instructions across all program modules
+    pub instructions: usize,
+
+    /// Account structs
+    pub account_structs: usize,
+
+    /// Raw accounts
+    pub raw_accounts: usize,
+}
+
+impl fmt::Display for ProgramCounts {
+    /// Formats one `kind: count` line per item kind
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "program modules: {}", self.program_modules)?;
+        writeln!(f, "instructions: {}", self.instructions)?;
+        writeln!(f, "account structs: {}", self.account_structs)?;
+        write!(f, "raw accounts: {}", self.raw_accounts)
+    }
 }
 
 /// Represents a complete Anchor program
 ///
 /// A program contains program modules, account structures, and raw accounts.
 /// It is the root object for representing an Anchor program's structure.
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Program {
     /// Program modules (with #[program] attribute)
     pub program_modules: Vec<ProgramModule>,
@@ -39,6 +220,18 @@ pub struct Program {
     /// Raw account structs (with #[account])
     pub raw_accounts: Vec<RawAccount>,
 
+    /// Processed top-level items in source order
+    pub item_order: Vec<ItemRef>,
+
+    /// Top-level use declarations, including `pub use` re-exports
+    pub imports: Vec<Import>,
+
+    /// Error enums (with #[error_code])
+    pub error_enums: Vec<ErrorEnum>,
+
+    /// Inner attributes of the file (e.g., "allow(unused)" from `#![allow(unused)]`)
+    pub file_attributes: Vec<String>,
+
     /// Source file path (if available)
     pub source_path: Option<String>,
 }
@@ -64,6 +257,87 @@ impl Program {
         self.raw_accounts.push(account);
     }
 
+    /// Record a processed top-level item in source order
+    pub fn add_item_ref(&mut self, kind: ItemKind, name: impl Into<String>) {
+        self.item_order.push(ItemRef::new(kind, name));
+    }
+
+    /// Add a top-level import to the program
+    pub fn add_import(&mut self, import: Import) {
+        self.imports.push(import);
+    }
+
+    /// Add an error enum to the program
+    pub fn add_error_enum(&mut self, error_enum: ErrorEnum) {
+        self.error_enums.push(error_enum);
+    }
+
+    /// Record an inner attribute of the file
+    pub fn add_file_attribute(&mut self, attribute: impl Into<String>) {
+        self.file_attributes.push(attribute.into());
+    }
+
+    /// Merge another program, such as a sibling file of the same crate, into this one
+    ///
+    /// Items from `other` are appended after this program's items, so an
+    /// instruction in one file can refer to an account struct defined in
+    /// another. The source path is kept, or taken from `other` if unset.
+    pub fn merge(&mut self, other: Program) {
+        self.program_modules.extend(other.program_modules);
+        self.account_structs.extend(other.account_structs);
+        self.raw_accounts.extend(other.raw_accounts);
+        self.item_order.extend(other.item_order);
+        self.imports.extend(other.imports);
+        self.error_enums.extend(other.error_enums);
+        for attribute in other.file_attributes {
+            if !self.file_attributes.contains(&attribute) {
+                self.file_attributes.push(attribute);
+            }
+        }
+        if self.source_path.is_none() {
+            self.source_path = other.source_path;
+        }
+    }
+
+    /// Sort modules, account structs, raw accounts and their fields by name
+    ///
+    /// Gives serialized output a stable order across refactors and merges.
+    /// Account struct field order is significant to Anchor, so this is for
+    /// output only; `item_order` keeps the source order.
+    pub fn sort_by_name(&mut self) {
+        self.program_modules.sort_by(|a, b| a.name.cmp(&b.name));
+        self.account_structs.sort_by(|a, b| a.name.cmp(&b.name));
+        for account in &mut self.account_structs {
+            account.fields.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        self.raw_accounts.sort_by(|a, b| a.name.cmp(&b.name));
+        for account in &mut self.raw_accounts {
+            account.fields.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+
+    /// Whether this looks like an Anchor program
+    ///
+    /// True when at least one `#[program]` module was found; a plain Rust
+    /// file parses without error but has none.
+    pub fn is_anchor(&self) -> bool {
+        !self.program_modules.is_empty()
+    }
+
+    /// Count the program's modules, instructions and accounts
+    pub fn counts(&self) -> ProgramCounts {
+        ProgramCounts {
+            program_modules: self.program_modules.len(),
+            instructions: self
+                .program_modules
+                .iter()
+                .map(|m| m.instructions.len())
+                .sum(),
+            account_structs: self.account_structs.len(),
+            raw_accounts: self.raw_accounts.len(),
+        }
+    }
+
     /// Find a program module by name
     pub fn find_program_module(&self, name: &str) -> Option<&ProgramModule> {
         self.program_modules.iter().find(|m| m.name == name)
@@ -79,6 +353,11 @@ impl Program {
         self.raw_accounts.iter().find(|a| a.name == name)
     }
 
+    /// Find an error enum by name
+    pub fn find_error_enum(&self, name: &str) -> Option<&ErrorEnum> {
+        self.error_enums.iter().find(|e| e.name == name)
+    }
+
     /// Set the source path (builder pattern)
     pub fn with_source_path(mut self, path: impl Into<String>) -> Self {
         self.source_path = Some(path.into());
@@ -111,6 +390,8 @@ impl ProgramModule {
             name: name.into(),
             visibility: visibility.into(),
             instructions: Vec::new(),
+            uses: Vec::new(),
+            cfg: Vec::new(),
         }
     }
 
@@ -124,6 +405,21 @@ impl ProgramModule {
         self.instructions.iter().find(|i| i.name == name)
     }
 
+    /// Add a use statement to the program module
+    pub fn add_use(&mut self, path: impl Into<String>) {
+        self.uses.push(path.into());
+    }
+
+    /// Add a cfg predicate to the program module
+    pub fn add_cfg(&mut self, predicate: CfgPredicate) {
+        self.cfg.push(predicate);
+    }
+
+    /// Whether every cfg predicate on the module holds
+    pub fn cfg_satisfied(&self, is_set: &impl Fn(&str, Option<&str>) -> bool) -> bool {
+        self.cfg.iter().all(|p| p.evaluate(is_set))
+    }
+
     /// Set instructions (builder pattern)
     pub fn with_instructions(mut self, instructions: Vec<Instruction>) -> Self {
         self.instructions = instructions;
@@ -184,6 +480,56 @@ mod tests {
         assert_eq!(program.raw_accounts[0].name, "MyRawAccount");
     }
 
+    #[test]
+    fn test_program_counts() {
+        let mut module = ProgramModule::new("vault", "pub");
+        module.add_instruction(Instruction::new("deposit", "pub"));
+        module.add_instruction(Instruction::new("withdraw", "pub"));
+
+        let program = Program::new()
+            .with_program_module(module)
+            .with_program_module(ProgramModule::new("admin", "pub"))
+            .with_account_struct(Account::new("Deposit", "pub"))
+            .with_raw_account(RawAccount::new("Vault", "pub"));
+
+        assert_eq!(
+            program.counts(),
+            ProgramCounts {
+                program_modules: 2,
+                instructions: 2,
+                account_structs: 1,
+                raw_accounts: 1,
+            }
+        );
+        assert_eq!(
+            program.counts().to_string(),
+            "program modules: 2\ninstructions: 2\naccount structs: 1\nraw accounts: 1"
+        );
+    }
+
+    #[test]
+    fn test_program_merge() {
+        let mut handlers = Program::new()
+            .with_program_module(ProgramModule::new("vault", "pub"))
+            .with_source_path("src/lib.rs");
+        handlers.add_item_ref(ItemKind::ProgramModule, "vault");
+
+        let mut accounts = Program::new()
+            .with_account_struct(Account::new("Deposit", "pub"))
+            .with_raw_account(RawAccount::new("Vault", "pub"))
+            .with_source_path("src/state.rs");
+        accounts.add_item_ref(ItemKind::AccountStruct, "Deposit");
+        accounts.add_item_ref(ItemKind::RawAccount, "Vault");
+
+        handlers.merge(accounts);
+
+        assert!(handlers.find_program_module("vault").is_some());
+        assert!(handlers.find_account_struct("Deposit").is_some());
+        assert!(handlers.find_raw_account("Vault").is_some());
+        assert_eq!(handlers.item_order.len(), 3);
+        assert_eq!(handlers.source_path.as_deref(), Some("src/lib.rs"));
+    }
+
     #[test]
     fn test_program_find_methods() {
         let mut program = Program::new();
@@ -5,12 +5,35 @@
 
 use crate::error::{ParseError, Result};
 use crate::model::{
-    Account, AccountField, Constraint, Instruction, Parameter, Program, ProgramModule, RawAccount,
-    RawAccountField,
+    Account, AccountField, CfgPredicate, Constraint, ErrorEnum, Import, Instruction, ItemKind,
+    Parameter, Program, ProgramModule, RawAccount, RawAccountField,
 };
+use crate::parser::features::ParseFeatures;
 use crate::parser::predicates;
+use crate::parser::render;
+use proc_macro2::{Spacing, TokenTree};
 use quote::ToTokens;
-use syn::{Attribute, File, Item, ItemFn, ItemStruct, Visibility};
+use syn::parse::discouraged::Speculative;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{
+    Attribute, File, Item, ItemFn, ItemStruct, ItemUse, LitInt, LitStr, Token, UseTree, Visibility,
+};
+
+/// Sysvar types detected in instruction bodies
+const SYSVARS: &[&str] = &[
+    "Clock",
+    "Rent",
+    "EpochSchedule",
+    "EpochRewards",
+    "Fees",
+    "LastRestartSlot",
+    "RecentBlockhashes",
+    "SlotHashes",
+    "SlotHistory",
+    "StakeHistory",
+];
 
 /// Convert a parsed syntax tree to our Program model
 ///
@@ -25,48 +48,185 @@ use syn::{Attribute, File, Item, ItemFn, ItemStruct, Visibility};
 ///
 /// A Program model representing the Anchor program
 pub fn convert_file(file: &File) -> Result<Program> {
+    convert_file_filtered(file, &ConvertOptions::default())
+}
+
+/// Options controlling which item kinds are converted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertOptions {
+    /// Convert instructions inside program modules
+    pub include_instructions: bool,
+
+    /// Convert #[derive(Accounts)] structs
+    pub include_accounts: bool,
+
+    /// Convert #[account] structs
+    pub include_raw_accounts: bool,
+
+    /// Only treat `pub` functions as instructions, as Anchor does, so
+    /// private helpers taking a `Context` are skipped
+    pub only_pub_instructions: bool,
+
+    /// Also collect instructions from inline modules nested in a program
+    /// module; Anchor doesn't dispatch to these, so they're skipped by default
+    pub collect_nested_instructions: bool,
+
+    /// Prefix instructions from nested modules with the module path, so
+    /// `handlers::foo` becomes `handlers_foo` instead of `foo`; only applies
+    /// with `collect_nested_instructions` on
+    pub prefix_nested_instructions: bool,
+
+    /// When set, skip program modules whose cfg predicates don't hold
+    pub features: Option<ParseFeatures>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            include_instructions: true,
+            include_accounts: true,
+            include_raw_accounts: true,
+            only_pub_instructions: true,
+            collect_nested_instructions: false,
+            prefix_nested_instructions: false,
+            features: None,
+        }
+    }
+}
+
+/// Convert a parsed syntax tree, skipping item kinds excluded by the options
+///
+/// Program modules are always recorded; with `include_instructions` off
+/// their instruction lists are left empty.
+///
+/// # Arguments
+///
+/// * `file` - The parsed syntax tree
+/// * `options` - Which item kinds to convert
+///
+/// # Returns
+///
+/// A Program model containing only the requested item kinds
+pub fn convert_file_filtered(file: &File, options: &ConvertOptions) -> Result<Program> {
     let mut program = Program::new();
 
+    // Inner attributes (e.g., `#![allow(unused)]`) apply to the whole file
+    for attr in &file.attrs {
+        if matches!(attr.style, syn::AttrStyle::Inner(_)) && !attr.path().is_ident("doc") {
+            program.add_file_attribute(format_tokens(&attr.meta));
+        }
+    }
+
     // Process each item in the file
     for item in &file.items {
-        process_item(&mut program, item)?;
+        process_item(&mut program, item, options)?;
     }
 
+    shrink_program(&mut program);
     Ok(program)
 }
 
+/// Release the spare capacity left in a converted program's vectors
+///
+/// Vectors grow by doubling while items are pushed, so large generated
+/// files would otherwise keep up to half their item storage unused.
+fn shrink_program(program: &mut Program) {
+    for module in &mut program.program_modules {
+        for instruction in &mut module.instructions {
+            instruction.parameters.shrink_to_fit();
+            instruction.attributes.shrink_to_fit();
+        }
+        module.instructions.shrink_to_fit();
+        module.uses.shrink_to_fit();
+    }
+    for account in &mut program.account_structs {
+        for field in &mut account.fields {
+            field.constraints.shrink_to_fit();
+        }
+        account.fields.shrink_to_fit();
+    }
+    for account in &mut program.raw_accounts {
+        for field in &mut account.fields {
+            field.attributes.shrink_to_fit();
+        }
+        account.fields.shrink_to_fit();
+    }
+    program.program_modules.shrink_to_fit();
+    program.account_structs.shrink_to_fit();
+    program.raw_accounts.shrink_to_fit();
+    program.item_order.shrink_to_fit();
+    program.imports.shrink_to_fit();
+    program.error_enums.shrink_to_fit();
+    program.file_attributes.shrink_to_fit();
+}
+
 /// Process a top-level syntax item
-fn process_item(program: &mut Program, item: &Item) -> Result<()> {
+fn process_item(program: &mut Program, item: &Item, options: &ConvertOptions) -> Result<()> {
     match item {
-        Item::Mod(module) => {
-            if predicates::is_anchor_program(module) {
-                // Found a program module
-                let module_name = module.ident.to_string();
-                let visibility = format_visibility(&module.vis);
-
-                let mut program_module = ProgramModule::new(module_name, visibility);
-
-                // Process its contents if available
-                if let Some((_, items)) = &module.content {
-                    for item in items {
-                        process_program_item(&mut program_module, item)?;
-                    }
+        Item::Mod(module) if predicates::is_anchor_program(module) => {
+            // Found a program module
+            let module_name = module.ident.to_string();
+            let visibility = format_visibility(&module.vis);
+
+            let mut program_module = ProgramModule::new(module_name, visibility);
+            for predicate in extract_cfg(&module.attrs) {
+                program_module.add_cfg(predicate);
+            }
+
+            if let Some(features) = &options.features {
+                if !features.includes(&program_module) {
+                    return Ok(());
                 }
+            }
 
-                program.add_program_module(program_module);
+            // Process its contents if available
+            if let Some((_, items)) = &module.content {
+                for item in items {
+                    process_program_item(&mut program_module, item, options, None)?;
+                }
             }
+
+            program.add_item_ref(ItemKind::ProgramModule, &program_module.name);
+            program.add_program_module(program_module);
         }
         Item::Struct(structure) => {
             if predicates::is_account_struct(structure) {
-                // Convert to our Account model
-                let account = convert_account_struct(structure)?;
-                program.add_account_struct(account);
-            } else if predicates::is_raw_account(structure) {
+                if options.include_accounts {
+                    // Convert to our Account model
+                    let account = convert_account_struct(structure)?;
+                    program.add_item_ref(ItemKind::AccountStruct, &account.name);
+                    program.add_account_struct(account);
+                }
+            } else if predicates::is_raw_account(structure) && options.include_raw_accounts {
                 // Convert to our RawAccount model
                 let raw_account = convert_raw_account(structure)?;
+                program.add_item_ref(ItemKind::RawAccount, &raw_account.name);
                 program.add_raw_account(raw_account);
             }
         }
+        Item::Enum(item_enum) if predicates::is_error_enum(item_enum) => {
+            // Custom errors that constraints can map to with `@ Enum::Variant`
+            let mut error_enum = ErrorEnum::new(item_enum.ident.to_string());
+            for variant in &item_enum.variants {
+                let message = variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("msg"))
+                    .find_map(|attr| attr.parse_args::<LitStr>().ok());
+                match message {
+                    Some(message) => error_enum
+                        .add_variant_with_message(variant.ident.to_string(), message.value()),
+                    None => error_enum.add_variant(variant.ident.to_string()),
+                }
+            }
+            program.add_item_ref(ItemKind::ErrorEnum, &error_enum.name);
+            program.add_error_enum(error_enum);
+        }
+        Item::Use(item_use) => {
+            // Re-exports can bring account types into scope just like plain uses
+            let is_reexport = !matches!(item_use.vis, Visibility::Inherited);
+            program.add_import(Import::new(format_use(item_use), is_reexport));
+        }
         // Other items can be ignored or processed as needed
         _ => {}
     }
@@ -75,13 +235,42 @@ fn process_item(program: &mut Program, item: &Item) -> Result<()> {
 }
 
 /// Process an item within a program module
-fn process_program_item(program_module: &mut ProgramModule, item: &Item) -> Result<()> {
+///
+/// With `collect_nested_instructions` on, instructions in inline nested
+/// modules (e.g., `mod handlers { ... }`) are collected into the program
+/// module as well; `prefix` is the path of the nested module being
+/// processed, joined with `_` (e.g., "handlers").
+fn process_program_item(
+    program_module: &mut ProgramModule,
+    item: &Item,
+    options: &ConvertOptions,
+    prefix: Option<&str>,
+) -> Result<()> {
     match item {
-        Item::Fn(function) => {
-            if predicates::is_anchor_instruction(function) {
-                // Convert to our Instruction model
-                let instruction = convert_instruction(function)?;
-                program_module.add_instruction(instruction);
+        Item::Fn(function)
+            if options.include_instructions
+                && predicates::is_anchor_instruction(function)
+                && (!options.only_pub_instructions
+                    || matches!(function.vis, Visibility::Public(_))) =>
+        {
+            // Convert to our Instruction model
+            let mut instruction = convert_instruction(function)?;
+            if let Some(prefix) = prefix.filter(|_| options.prefix_nested_instructions) {
+                instruction.name = format!("{}_{}", prefix, instruction.name);
+            }
+            program_module.add_instruction(instruction);
+        }
+        Item::Use(item_use) if prefix.is_none() => {
+            // Keep track of names brought into scope for the instruction bodies
+            program_module.add_use(format_use(item_use));
+        }
+        Item::Mod(nested) if options.collect_nested_instructions => {
+            let nested_prefix = match prefix {
+                Some(prefix) => format!("{}_{}", prefix, nested.ident),
+                None => nested.ident.to_string(),
+            };
+            for item in nested.content.iter().flat_map(|(_, items)| items) {
+                process_program_item(program_module, item, options, Some(&nested_prefix))?;
             }
         }
         // Other items can be ignored or processed as needed
@@ -91,20 +280,120 @@ fn process_program_item(program_module: &mut ProgramModule, item: &Item) -> Resu
     Ok(())
 }
 
+/// Join the `///` doc lines among attributes, trimming each line
+fn extract_docs(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(s),
+                        ..
+                    }),
+                ..
+            }) => Some(s.value().trim().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Parse the predicates of `#[cfg(...)]` attributes
+///
+/// Attributes whose predicate can't be parsed are ignored.
+fn extract_cfg(attrs: &[Attribute]) -> Vec<CfgPredicate> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .filter_map(|attr| attr.parse_args::<syn::Meta>().ok())
+        .filter_map(|meta| parse_cfg_predicate(&meta))
+        .collect()
+}
+
+/// Convert one cfg predicate, recursing into `all`, `any` and `not`
+fn parse_cfg_predicate(meta: &syn::Meta) -> Option<CfgPredicate> {
+    match meta {
+        syn::Meta::Path(path) => Some(CfgPredicate::Option {
+            name: path.get_ident()?.to_string(),
+            value: None,
+        }),
+        syn::Meta::NameValue(name_value) => match &name_value.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(CfgPredicate::Option {
+                name: name_value.path.get_ident()?.to_string(),
+                value: Some(s.value()),
+            }),
+            _ => None,
+        },
+        syn::Meta::List(list) => {
+            let nested = list
+                .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+                .ok()?
+                .iter()
+                .map(parse_cfg_predicate)
+                .collect::<Option<Vec<_>>>()?;
+
+            match list.path.get_ident()?.to_string().as_str() {
+                "all" => Some(CfgPredicate::All(nested)),
+                "any" => Some(CfgPredicate::Any(nested)),
+                "not" if nested.len() == 1 => {
+                    Some(CfgPredicate::Not(Box::new(nested.into_iter().next()?)))
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
 /// Convert a syn ItemStruct to our Account model
 fn convert_account_struct(structure: &ItemStruct) -> Result<Account> {
     let name = structure.ident.to_string();
     let visibility = format_visibility(&structure.vis);
 
     let mut account = Account::new(name, visibility);
+    account.where_clause = structure
+        .generics
+        .where_clause
+        .as_ref()
+        .map(|clause| render::render_type(clause.to_token_stream()));
+
+    // Record the instruction arguments the struct's constraints can use
+    for attr in &structure.attrs {
+        if attr.path().is_ident("instruction") {
+            // A malformed attribute loses its arguments, not the whole file
+            let Ok(args) = attr.parse_args_with(parse_instruction_args) else {
+                continue;
+            };
+            for arg in args {
+                if let syn::FnArg::Typed(pat_type) = arg {
+                    let arg_name = format_tokens(&pat_type.pat);
+                    account.add_instruction_arg(Parameter::new(
+                        arg_name,
+                        format_type(&pat_type.ty)?,
+                        false,
+                    ));
+                }
+            }
+        }
+    }
 
     // Process fields
     for field in &structure.fields {
         if let Some(ident) = &field.ident {
             let field_name = ident.to_string();
-            let field_type = format_type(&field.ty);
+            let field_type = format_type(&field.ty)?;
 
             let mut account_field = AccountField::new(field_name, field_type);
+            account_field.documentation = extract_docs(&field.attrs);
 
             // Process account attribute constraints
             for attr in &field.attrs {
@@ -119,79 +408,118 @@ fn convert_account_struct(structure: &ItemStruct) -> Result<Account> {
 
     Ok(account)
 }
-/// Process the constraints in an #[account(...)] attribute
-fn process_account_constraints(attr: &Attribute, field: &mut AccountField) -> Result<()> {
-    // Get the attribute content as a string
-    let attr_str = attr.to_token_stream().to_string();
-
-    // Extract contents between parentheses
-    if let Some(start) = attr_str.find('(') {
-        if let Some(end) = attr_str.rfind(')') {
-            let content = &attr_str[start + 1..end];
-
-            // Parse the content manually
-            let mut constraints = Vec::new();
-            let mut current = String::new();
-            let mut depth = 0;
-
-            for c in content.chars() {
-                match c {
-                    '(' | '[' | '{' => {
-                        depth += 1;
-                        current.push(c);
-                    }
-                    ')' | ']' | '}' => {
-                        depth -= 1;
-                        current.push(c);
-                    }
-                    ',' if depth == 0 => {
-                        if !current.trim().is_empty() {
-                            constraints.push(current.trim().to_string());
-                            current.clear();
-                        }
-                    }
-                    _ => current.push(c),
-                }
-            }
 
-            if !current.trim().is_empty() {
-                constraints.push(current.trim().to_string());
+/// Parse the arguments of an `#[instruction(...)]` attribute
+///
+/// Arguments that don't parse are skipped up to the next `,`, keeping the
+/// well-formed ones.
+fn parse_instruction_args(input: ParseStream) -> syn::Result<Vec<syn::FnArg>> {
+    let mut args = Vec::new();
+    while !input.is_empty() {
+        let fork = input.fork();
+        match fork.parse::<syn::FnArg>() {
+            Ok(arg) if fork.is_empty() || fork.peek(Token![,]) => {
+                input.advance_to(&fork);
+                args.push(arg);
             }
-
-            // Process each constraint
-            for constraint in constraints {
-                if let Some(idx) = constraint.find('=') {
-                    let name = constraint[..idx].trim().to_string();
-                    let value = constraint[idx + 1..].trim().to_string();
-                    field.add_constraint(Constraint::with_value(name, value));
-                } else {
-                    field.add_constraint(Constraint::without_value(constraint));
+            _ => {
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    input.parse::<TokenTree>()?;
                 }
             }
+        }
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(args)
+}
 
-            return Ok(());
+/// Process the constraints in an #[account(...)] attribute
+///
+/// Constraints are split on top-level `,` tokens, so commas inside groups
+/// and string literals stay part of their constraint. Values are rendered
+/// from their tokens with rustfmt-style spacing (e.g., `[a, b, c]`).
+fn process_account_constraints(attr: &Attribute, field: &mut AccountField) -> Result<()> {
+    let syn::Meta::List(list) = &attr.meta else {
+        return Err(ParseError::Parse(
+            "Failed to parse account attribute".to_string(),
+        ));
+    };
+
+    let tokens: Vec<TokenTree> = list.tokens.clone().into_iter().collect();
+    let constraints = tokens
+        .split(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ','))
+        .filter(|constraint| !constraint.is_empty());
+
+    for constraint in constraints {
+        // The first lone `=` separates the name from the value, so `==`
+        // and `>=` inside the value are left alone
+        let eq = constraint.iter().enumerate().position(|(i, token)| {
+            matches!(token, TokenTree::Punct(p)
+            if p.as_char() == '='
+                && p.spacing() == Spacing::Alone
+                && !matches!(
+                    i.checked_sub(1).map(|prev| &constraint[prev]),
+                    Some(TokenTree::Punct(prev)) if prev.spacing() == Spacing::Joint
+                ))
+        });
+
+        match eq {
+            Some(eq) => {
+                let name = token_string(&constraint[..eq]);
+                let value = render::render_expr(constraint[eq + 1..].iter().cloned());
+                field.add_constraint(Constraint::with_value(name, value));
+            }
+            None => field.add_constraint(Constraint::without_value(token_string(constraint))),
         }
     }
 
-    Err(ParseError::Parse(
-        "Failed to parse account attribute".to_string(),
-    ))
+    Ok(())
 }
+
+/// Render a run of tokens as `to_string` would within the whole stream
+fn token_string(tokens: &[TokenTree]) -> String {
+    tokens
+        .iter()
+        .cloned()
+        .collect::<proc_macro2::TokenStream>()
+        .to_string()
+}
+
 /// Convert a syn ItemStruct to our RawAccount model
 fn convert_raw_account(structure: &ItemStruct) -> Result<RawAccount> {
     let name = structure.ident.to_string();
     let visibility = format_visibility(&structure.vis);
 
     let mut raw_account = RawAccount::new(name, visibility);
+    raw_account.derives_init_space = predicates::has_derive(structure, "InitSpace");
+    raw_account.discriminator_override = structure
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("account"))
+        .find_map(parse_discriminator_override);
 
     // Process fields
     for field in &structure.fields {
         if let Some(ident) = &field.ident {
             let field_name = ident.to_string();
-            let field_type = format_type(&field.ty);
+            let field_type = format_type(&field.ty)?;
             let field_vis = format_visibility(&field.vis);
 
-            let raw_field = RawAccountField::new(field_name, field_type, field_vis);
+            let mut raw_field = RawAccountField::new(field_name, field_type, field_vis);
+
+            // Record field attributes such as #[max_len(50)] used by InitSpace
+            for attr in &field.attrs {
+                if attr.path().is_ident("doc") {
+                    continue;
+                }
+                if attr.path().is_ident("max_len") {
+                    raw_field.max_len = parse_max_len(attr);
+                }
+                raw_field.add_attribute(format_tokens(&attr.meta));
+            }
+
             raw_account.add_field(raw_field);
         }
     }
@@ -199,6 +527,54 @@ fn convert_raw_account(structure: &ItemStruct) -> Result<RawAccount> {
     Ok(raw_account)
 }
 
+/// Parse an explicit discriminator from `#[account(discriminator = ...)]`
+///
+/// Accepts a byte array (`[1, 2, 3]`) or a single byte (`1`). Other
+/// expressions, such as constants, can't be evaluated and are ignored.
+fn parse_discriminator_override(attr: &Attribute) -> Option<Vec<u8>> {
+    let args = attr
+        .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        .ok()?;
+
+    let value = args.iter().find_map(|meta| match meta {
+        syn::Meta::NameValue(name_value) if name_value.path.is_ident("discriminator") => {
+            Some(&name_value.value)
+        }
+        _ => None,
+    })?;
+
+    match value {
+        syn::Expr::Array(array) => array.elems.iter().map(parse_byte).collect(),
+        other => parse_byte(other).map(|byte| vec![byte]),
+    }
+}
+
+/// Parse an integer literal expression as a byte
+fn parse_byte(expr: &syn::Expr) -> Option<u8> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse every length from a `#[max_len(N, ...)]` attribute, outermost first
+///
+/// Returns no lengths if any of them isn't an integer literal.
+fn parse_max_len(attr: &Attribute) -> Vec<usize> {
+    attr.parse_args_with(Punctuated::<LitInt, Token![,]>::parse_terminated)
+        .ok()
+        .and_then(|lengths| {
+            lengths
+                .iter()
+                .map(|length| length.base10_parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Convert a syn ItemFn to our Instruction model
 fn convert_instruction(function: &ItemFn) -> Result<Instruction> {
     let name = function.sig.ident.to_string();
@@ -206,43 +582,113 @@ fn convert_instruction(function: &ItemFn) -> Result<Instruction> {
 
     let mut instruction = Instruction::new(name, visibility);
 
+    // Record non-doc attributes (e.g., #[interface], #[access_control(...)])
+    for attr in &function.attrs {
+        if !attr.path().is_ident("doc") {
+            instruction.add_attribute(format_tokens(&attr.meta));
+        }
+    }
+
     // Set return type if available
     if let syn::ReturnType::Type(_, ty) = &function.sig.output {
-        instruction.set_return_type(format_type(ty));
+        instruction.set_return_type(format_type(ty)?);
+
+        if let Some(ok_type) = get_result_ok_type(ty) {
+            instruction.set_ok_type(ok_type);
+        }
     }
 
     // Process parameters
     for input in &function.sig.inputs {
-        match input {
-            syn::FnArg::Typed(pat_type) => {
-                // Get parameter name
-                let param_name = match &*pat_type.pat {
-                    syn::Pat::Ident(ident) => ident.ident.to_string(),
-                    _ => "unnamed".to_string(),
-                };
-
-                // Check if this is a Context parameter
-                let (is_context, context_type) = get_context_info(&pat_type.ty);
-
-                let param_type = format_type(&pat_type.ty);
-
-                // If it's a Context, set the context type
-                if is_context {
-                    if let Some(ctx_type) = context_type {
-                        instruction.set_context_type(ctx_type);
-                    }
+        if let syn::FnArg::Typed(pat_type) = input {
+            // Get parameter name
+            let param_name = match &*pat_type.pat {
+                syn::Pat::Ident(ident) => ident.ident.to_string(),
+                _ => "unnamed".to_string(),
+            };
+
+            let param_type = format_type(&pat_type.ty)?;
+
+            // Check if this is a Context parameter
+            let (is_context, context_type) = get_context_info(&pat_type.ty);
+
+            // If it's a Context, set the context type
+            if is_context {
+                if let Some(ctx_type) = context_type {
+                    instruction.set_context_type(ctx_type);
                 }
+            }
 
-                let parameter = Parameter::new(param_name, param_type, is_context);
-                instruction.add_parameter(parameter);
+            let mut parameter = Parameter::new(param_name, param_type, is_context);
+            if let Some(docs) = extract_docs(&pat_type.attrs) {
+                parameter = parameter.with_documentation(docs);
             }
-            _ => {}
+            instruction.add_parameter(parameter);
         }
     }
 
+    for sysvar in find_sysvars(&function.block) {
+        instruction.add_sysvar(sysvar);
+    }
+    for call in find_calls(&function.block) {
+        instruction.add_call(call);
+    }
+
     Ok(instruction)
 }
 
+/// Find sysvar types used as a path prefix (e.g., `Clock::get()`) in a body
+fn find_sysvars(block: &syn::Block) -> Vec<&'static str> {
+    let tokens = block.to_token_stream().to_string();
+    let words: Vec<&str> = tokens.split_whitespace().collect();
+
+    words
+        .windows(2)
+        .filter(|pair| pair[1] == "::")
+        .filter_map(|pair| SYSVARS.iter().find(|sysvar| **sysvar == pair[0]).copied())
+        .collect()
+}
+
+/// Find functions called by name (e.g., `helper(ctx)` or `crate::helper(ctx)`) in a body
+///
+/// Only single-segment paths and paths starting with `crate`, `super` or
+/// `self` are included, so CPI calls such as `token::transfer(..)` aren't
+/// mistaken for local functions. Method calls (`x.helper()`), macro
+/// arguments (`msg!(...)`) and tuple constructors named in upper camel case
+/// (`Ok(())`, `Some(x)`) are not included.
+fn find_calls(block: &syn::Block) -> Vec<String> {
+    struct CallVisitor(Vec<String>);
+
+    impl<'ast> Visit<'ast> for CallVisitor {
+        fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+            if let syn::Expr::Path(syn::ExprPath {
+                qself: None, path, ..
+            }) = &*call.func
+            {
+                let local = path.leading_colon.is_none()
+                    && (path.segments.len() == 1
+                        || ["crate", "super", "self"]
+                            .iter()
+                            .any(|root| path.segments[0].ident == root));
+                let name = path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string());
+                if let Some(name) = name.filter(|name| {
+                    local && name.starts_with(|c: char| c.is_lowercase() || c == '_')
+                }) {
+                    self.0.push(name);
+                }
+            }
+            visit::visit_expr_call(self, call);
+        }
+    }
+
+    let mut visitor = CallVisitor(Vec::new());
+    visitor.visit_block(block);
+    visitor.0
+}
+
 /// Analyze a type to determine if it's a Context type and extract its generic parameter
 fn get_context_info(ty: &syn::Type) -> (bool, Option<String>) {
     if let syn::Type::Path(type_path) = ty {
@@ -255,10 +701,8 @@ fn get_context_info(ty: &syn::Type) -> (bool, Option<String>) {
             // It's a Context, now extract the generic type
             if let Some(segment) = type_path.path.segments.last() {
                 if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                    if let Some(arg) = args.args.first() {
-                        if let syn::GenericArgument::Type(inner_ty) = arg {
-                            return (true, Some(format_type(inner_ty)));
-                        }
+                    if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
+                        return (true, Some(format_tokens(inner_ty)));
                     }
                 }
             }
@@ -268,6 +712,24 @@ fn get_context_info(ty: &syn::Type) -> (bool, Option<String>) {
     (false, None)
 }
 
+/// Extract the success type from a `Result<T>` return type
+///
+/// Handles path-qualified aliases such as `anchor_lang::Result<T>` by looking
+/// at the last path segment.
+fn get_result_ok_type(ty: &syn::Type) -> Option<String> {
+    if let syn::Type::Path(type_path) = ty {
+        let segment = type_path.path.segments.last()?;
+        if segment.ident == "Result" {
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(syn::GenericArgument::Type(ok_ty)) = args.args.first() {
+                    return Some(format_tokens(ok_ty));
+                }
+            }
+        }
+    }
+    None
+}
+
 /// Format a visibility to a string
 fn format_visibility(vis: &Visibility) -> String {
     match vis {
@@ -279,43 +741,102 @@ fn format_visibility(vis: &Visibility) -> String {
     }
 }
 
+/// Format a use item to a path string (e.g., "super::*")
+pub(crate) fn format_use(item_use: &ItemUse) -> String {
+    let prefix = if item_use.leading_colon.is_some() {
+        "::"
+    } else {
+        ""
+    };
+    format!("{}{}", prefix, format_use_tree(&item_use.tree))
+}
+
+/// Format a use tree recursively
+fn format_use_tree(tree: &UseTree) -> String {
+    match tree {
+        UseTree::Path(path) => format!("{}::{}", path.ident, format_use_tree(&path.tree)),
+        UseTree::Name(name) => name.ident.to_string(),
+        UseTree::Rename(rename) => format!("{} as {}", rename.ident, rename.rename),
+        UseTree::Glob(_) => "*".to_string(),
+        UseTree::Group(group) => {
+            let items: Vec<String> = group.items.iter().map(format_use_tree).collect();
+            format!("{{{}}}", items.join(", "))
+        }
+    }
+}
+
+/// Maximum generic nesting depth accepted for a single type
+const MAX_TYPE_DEPTH: usize = 64;
+
 /// Format a type to a string
-fn format_type(ty: &syn::Type) -> String {
-    let raw = ty.to_token_stream().to_string();
-
-    // First, normalize spaces around punctuation
-    let intermediate = raw
-        .replace(" : ", ":")
-        .replace(": ", ":")
-        .replace(" :", ":")
-        .replace(" < ", "<")
-        .replace("< ", "<")
-        .replace(" <", "<")
-        .replace(" > ", ">")
-        .replace("> ", ">")
-        .replace(" >", ">")
-        .replace(" ( ", "(")
-        .replace("( ", "(")
-        .replace(" (", "(")
-        .replace(" ) ", ")")
-        .replace(") ", ")")
-        .replace(" )", ")")
-        .replace(" [ ", "[")
-        .replace("[ ", "[")
-        .replace(" [", "[")
-        .replace(" ] ", "]")
-        .replace("] ", "]")
-        .replace(" ]", "]")
-        .replace(" , ", ",")
-        .replace(", ", ",")
-        .replace(" ,", ",");
-
-    // Normalize any remaining multiple spaces to single spaces
-    let result = intermediate
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ");
+///
+/// Fails for types nested deeper than [`MAX_TYPE_DEPTH`] rather than
+/// recursing without bound on pathological input.
+fn format_type(ty: &syn::Type) -> Result<String> {
+    if type_exceeds_depth(ty, MAX_TYPE_DEPTH) {
+        return Err(ParseError::LimitExceeded(format!(
+            "type nesting depth exceeds maximum of {}",
+            MAX_TYPE_DEPTH
+        )));
+    }
+
+    Ok(format_tokens(ty))
+}
 
+/// Check whether a type nests deeper than `remaining` levels
+///
+/// Stops descending once the budget is spent, so the check itself is
+/// bounded regardless of input.
+fn type_exceeds_depth(ty: &syn::Type, remaining: usize) -> bool {
+    let Some(remaining) = remaining.checked_sub(1) else {
+        return true;
+    };
+
+    match ty {
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .iter()
+            .any(|segment| match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| {
+                    matches!(arg, syn::GenericArgument::Type(inner) if type_exceeds_depth(inner, remaining))
+                }),
+                _ => false,
+            }),
+        syn::Type::Reference(reference) => type_exceeds_depth(&reference.elem, remaining),
+        syn::Type::Ptr(ptr) => type_exceeds_depth(&ptr.elem, remaining),
+        syn::Type::Slice(slice) => type_exceeds_depth(&slice.elem, remaining),
+        syn::Type::Array(array) => type_exceeds_depth(&array.elem, remaining),
+        syn::Type::Paren(paren) => type_exceeds_depth(&paren.elem, remaining),
+        syn::Type::Group(group) => type_exceeds_depth(&group.elem, remaining),
+        syn::Type::Tuple(tuple) => tuple
+            .elems
+            .iter()
+            .any(|elem| type_exceeds_depth(elem, remaining)),
+        _ => false,
+    }
+}
+
+/// Punctuation that token strings are tightened around
+const TIGHT_PUNCT: &[char] = &[':', '<', '>', '(', ')', '[', ']', ','];
+
+/// Format any syntax element to a compact token string
+///
+/// Drops the spaces `to_string` puts next to [`TIGHT_PUNCT`] characters and
+/// collapses the rest to single spaces, building the result in one pass.
+fn format_tokens(tokens: &impl ToTokens) -> String {
+    let raw = tokens.to_token_stream().to_string();
+    let mut result = String::with_capacity(raw.len());
+
+    for word in raw.split_whitespace() {
+        let tight = result.ends_with(TIGHT_PUNCT) || word.starts_with(TIGHT_PUNCT);
+        if !result.is_empty() && !tight {
+            result.push(' ');
+        }
+        result.push_str(word);
+    }
+
+    result.shrink_to_fit();
     result
 }
 
@@ -344,7 +865,7 @@ mod tests {
         let mut program = Program::new();
 
         // Process the module
-        process_item(&mut program, &Item::Mod(module)).unwrap();
+        process_item(&mut program, &Item::Mod(module), &ConvertOptions::default()).unwrap();
 
         // Verify the result
         assert_eq!(program.program_modules.len(), 1);
@@ -372,6 +893,409 @@ mod tests {
         assert!(!update_instr.parameters[1].is_context);
     }
 
+    #[test]
+    fn test_convert_program_module_uses() {
+        let module = parse_quote! {
+            #[program]
+            pub mod my_program {
+                use super::*;
+                use crate::state::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+        };
+
+        let mut program = Program::new();
+        process_item(&mut program, &Item::Mod(module), &ConvertOptions::default()).unwrap();
+
+        let program_module = &program.program_modules[0];
+        assert_eq!(program_module.uses, vec!["super::*", "crate::state::*"]);
+        assert_eq!(program_module.instructions.len(), 1);
+    }
+
+    #[test]
+    fn test_convert_top_level_imports() {
+        let file: File = parse_quote! {
+            use anchor_lang::prelude::*;
+            pub use crate::state::Vault;
+            pub(crate) use crate::errors::*;
+        };
+
+        let program = convert_file(&file).unwrap();
+        assert_eq!(
+            program.imports,
+            vec![
+                Import::new("anchor_lang::prelude::*", false),
+                Import::new("crate::state::Vault", true),
+                Import::new("crate::errors::*", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_instruction_ok_type() {
+        let function: ItemFn = parse_quote! {
+            pub fn get_balance(ctx: Context<GetBalance>) -> anchor_lang::Result<u64> {
+                Ok(0)
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(
+            instruction.return_type,
+            Some("anchor_lang::Result<u64>".to_string())
+        );
+        assert_eq!(instruction.ok_type, Some("u64".to_string()));
+
+        let function: ItemFn = parse_quote! {
+            pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                Ok(())
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(instruction.ok_type, Some("()".to_string()));
+    }
+
+    #[test]
+    fn test_convert_instruction_attributes() {
+        let function: ItemFn = parse_quote! {
+            /// Transfer hook entry point
+            #[interface(spl_transfer_hook_interface::execute)]
+            #[access_control(check(&ctx))]
+            pub fn execute(ctx: Context<Execute>, amount: u64) -> Result<()> {
+                Ok(())
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(
+            instruction.attributes,
+            vec![
+                "interface(spl_transfer_hook_interface::execute)",
+                "access_control(check(& ctx))"
+            ]
+        );
+        assert!(instruction.has_attribute("interface"));
+    }
+
+    #[test]
+    fn test_convert_instruction_sysvars() {
+        let function: ItemFn = syn::parse_quote! {
+            pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                let now = Clock::get()?.unix_timestamp;
+                let later = Clock::get()?.slot;
+                let rent = Rent::get()?;
+                msg!("Clock is not a sysvar reference here");
+                Ok(())
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(instruction.sysvars, vec!["Clock", "Rent"]);
+    }
+
+    #[test]
+    fn test_convert_instruction_calls() {
+        let function: ItemFn = syn::parse_quote! {
+            pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                if (amount > 0) {
+                    helper(&ctx)?;
+                }
+                crate::vault::settle(ctx, amount)?;
+                ctx.accounts.ignored_method(amount);
+                msg!("not a call");
+                helper(&ctx)?;
+                Ok(())
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(instruction.calls, vec!["helper", "settle"]);
+    }
+
+    #[test]
+    fn test_convert_instruction_calls_skip_strings_and_cpi() {
+        let function: ItemFn = syn::parse_quote! {
+            pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+                msg!("will not withdraw (ever)");
+                require!(amount > 0, "see withdraw (docs)");
+                token::transfer(cpi_ctx, amount)?;
+                ::vault::settle(ctx, amount)?;
+                super::helpers::settle(ctx, amount)?;
+                Ok(())
+            }
+        };
+
+        let instruction = convert_instruction(&function).unwrap();
+        assert_eq!(instruction.calls, vec!["settle"]);
+    }
+
+    #[test]
+    fn test_convert_file_filtered() {
+        let file: File = parse_quote! {
+            #[program]
+            pub mod token_program {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+
+                pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                #[account(mut)]
+                pub authority: Signer<'info>,
+            }
+
+            #[derive(Accounts)]
+            pub struct Transfer<'info> {
+                pub authority: Signer<'info>,
+            }
+
+            #[account]
+            pub struct TokenAccount {
+                pub owner: Pubkey,
+                pub amount: u64,
+            }
+        };
+
+        let options = ConvertOptions {
+            include_instructions: false,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+
+        assert_eq!(program.program_modules.len(), 1);
+        assert!(program.program_modules[0].instructions.is_empty());
+        assert_eq!(program.account_structs.len(), 2);
+        assert_eq!(program.raw_accounts.len(), 1);
+
+        let options = ConvertOptions {
+            include_accounts: false,
+            include_raw_accounts: false,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+
+        assert_eq!(program.program_modules[0].instructions.len(), 2);
+        assert!(program.account_structs.is_empty());
+        assert!(program.raw_accounts.is_empty());
+    }
+
+    #[test]
+    fn test_convert_only_pub_instructions() {
+        let file: File = parse_quote! {
+            #[program]
+            pub mod helpers {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    helper(ctx)
+                }
+
+                fn helper(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+        };
+
+        let names = |program: &Program| -> Vec<String> {
+            program.program_modules[0]
+                .instructions
+                .iter()
+                .map(|i| i.name.clone())
+                .collect()
+        };
+
+        let program = convert_file(&file).unwrap();
+        assert_eq!(names(&program), ["initialize"]);
+
+        let options = ConvertOptions {
+            only_pub_instructions: false,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+        assert_eq!(names(&program), ["initialize", "helper"]);
+    }
+
+    #[test]
+    fn test_convert_prefix_nested_instructions() {
+        let file: File = parse_quote! {
+            #[program]
+            pub mod routed {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+
+                pub mod handlers {
+                    use super::*;
+
+                    pub fn foo(ctx: Context<Foo>) -> Result<()> {
+                        Ok(())
+                    }
+
+                    pub mod admin {
+                        pub fn reset(ctx: Context<Reset>) -> Result<()> {
+                            Ok(())
+                        }
+                    }
+                }
+            }
+        };
+
+        let names = |program: &Program| -> Vec<String> {
+            program.program_modules[0]
+                .instructions
+                .iter()
+                .map(|i| i.name.clone())
+                .collect()
+        };
+
+        // Anchor only dispatches to the program module's own functions
+        let program = convert_file(&file).unwrap();
+        assert_eq!(names(&program), ["initialize"]);
+
+        // Prefixing alone doesn't collect nested instructions
+        let options = ConvertOptions {
+            prefix_nested_instructions: true,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+        assert_eq!(names(&program), ["initialize"]);
+
+        let options = ConvertOptions {
+            collect_nested_instructions: true,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+        assert_eq!(names(&program), ["initialize", "foo", "reset"]);
+        // Only the program module's own uses are recorded
+        assert_eq!(program.program_modules[0].uses, ["super::*"]);
+
+        let options = ConvertOptions {
+            collect_nested_instructions: true,
+            prefix_nested_instructions: true,
+            ..ConvertOptions::default()
+        };
+        let program = convert_file_filtered(&file, &options).unwrap();
+        assert_eq!(
+            names(&program),
+            ["initialize", "handlers_foo", "handlers_admin_reset"]
+        );
+    }
+
+    #[test]
+    fn test_convert_file_item_order() {
+        let file: File = parse_quote! {
+            #[derive(Accounts)]
+            pub struct Initialize<'info> {
+                pub authority: Signer<'info>,
+            }
+
+            #[program]
+            pub mod ordered {
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            pub struct Helper {}
+
+            #[account]
+            pub struct State {
+                pub value: u64,
+            }
+        };
+
+        let program = convert_file(&file).unwrap();
+
+        let order: Vec<(ItemKind, &str)> = program
+            .item_order
+            .iter()
+            .map(|item| (item.kind, item.name.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (ItemKind::AccountStruct, "Initialize"),
+                (ItemKind::ProgramModule, "ordered"),
+                (ItemKind::RawAccount, "State"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_file_attributes() {
+        let program = crate::parse_str(
+            r#"
+            #![allow(unused)]
+            #![cfg_attr(not(feature = "std"), no_std)]
+            //! Crate docs are not attributes to regenerate
+
+            #[program]
+            pub mod empty {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            program.file_attributes,
+            vec!["allow(unused)", r#"cfg_attr(not(feature = "std"),no_std)"#]
+        );
+    }
+
+    #[test]
+    fn test_convert_error_enum() {
+        let file: File = parse_quote! {
+            #[error_code]
+            pub enum VaultError {
+                #[msg("Amount too small")]
+                TooSmall,
+                Unauthorized,
+            }
+
+            pub enum NotAnError {
+                Other,
+            }
+        };
+
+        let program = convert_file(&file).unwrap();
+
+        assert_eq!(program.error_enums.len(), 1);
+        let error_enum = program.find_error_enum("VaultError").unwrap();
+        assert_eq!(error_enum.variants, ["TooSmall", "Unauthorized"]);
+        assert!(error_enum.has_variant("Unauthorized"));
+        assert_eq!(error_enum.message("TooSmall"), Some("Amount too small"));
+        assert_eq!(error_enum.message("Unauthorized"), None);
+        assert_eq!(program.item_order[0].kind, ItemKind::ErrorEnum);
+    }
+
+    #[test]
+    fn test_format_use() {
+        let item: ItemUse = parse_quote!(
+            use anchor_spl::token::{self, Token, Mint as M};
+        );
+        assert_eq!(
+            format_use(&item),
+            "anchor_spl::token::{self, Token, Mint as M}"
+        );
+
+        let item: ItemUse = parse_quote!(
+            use ::std::collections::HashMap;
+        );
+        assert_eq!(format_use(&item), "::std::collections::HashMap");
+    }
+
     #[test]
     fn test_convert_account_struct() {
         // Create an account struct with syn
@@ -417,6 +1341,154 @@ mod tests {
             .any(|c| c.constraint_type == "payer"));
     }
 
+    #[test]
+    fn test_constraint_value_spacing() {
+        let program = crate::parse_str(
+            r#"
+            #[derive(Accounts)]
+            pub struct Open<'info> {
+                #[account(init, seeds = [a, b, c], bump, constraint = x.len() >= 2, payer = user)]
+                pub pda: Account<'info, Pda>,
+            }
+            "#,
+        )
+        .unwrap();
+
+        let field = &program.account_structs[0].fields[0];
+        let value = |name: &str| {
+            field
+                .constraints
+                .iter()
+                .find(|c| c.constraint_type == name)
+                .and_then(|c| c.value.as_deref())
+        };
+
+        assert_eq!(value("seeds"), Some("[a, b, c]"));
+        assert_eq!(value("constraint"), Some("x.len() >= 2"));
+        assert_eq!(value("payer"), Some("user"));
+        assert_eq!(field.constraints.len(), 5);
+    }
+
+    #[test]
+    fn test_constraint_value_with_comma_in_string() {
+        let program = crate::parse_str(
+            r#"
+            #[derive(Accounts)]
+            pub struct Open<'info> {
+                #[account(constraint = vault.name == "a,b", payer = user, seeds = [b"v"], bump)]
+                pub vault: Account<'info, Vault>,
+            }
+            "#,
+        )
+        .unwrap();
+
+        let field = &program.account_structs[0].fields[0];
+        let names: Vec<&str> = field
+            .constraints
+            .iter()
+            .map(|c| c.constraint_type.as_str())
+            .collect();
+        let values: Vec<Option<&str>> = field
+            .constraints
+            .iter()
+            .map(|c| c.value.as_deref())
+            .collect();
+
+        assert_eq!(names, ["constraint", "payer", "seeds", "bump"]);
+        assert_eq!(
+            values,
+            [
+                Some(r#"vault.name == "a,b""#),
+                Some("user"),
+                Some(r#"[b"v"]"#),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_account_struct_where_clause() {
+        let program = crate::parse_str(
+            r#"
+            #[derive(Accounts)]
+            pub struct Close<'info, T>
+            where T: Owner   + Clone,
+            {
+                pub data: Account<'info, T>,
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            program.account_structs[0].where_clause.as_deref(),
+            Some("where T: Owner + Clone,")
+        );
+
+        // Tokens without source text render the same way
+        let file: File = parse_quote! {
+            #[derive(Accounts)]
+            pub struct Close<'info, T> where T: Owner {
+                pub data: Account<'info, T>,
+            }
+        };
+        let program = convert_file(&file).unwrap();
+        assert_eq!(
+            program.account_structs[0].where_clause.as_deref(),
+            Some("where T: Owner")
+        );
+
+        let file: File = parse_quote! {
+            #[derive(Accounts)]
+            pub struct Plain<'info> {
+                pub authority: Signer<'info>,
+            }
+        };
+        assert_eq!(
+            convert_file(&file).unwrap().account_structs[0].where_clause,
+            None
+        );
+    }
+
+    #[test]
+    fn test_convert_account_struct_instruction_args() {
+        let structure: ItemStruct = parse_quote! {
+            #[derive(Accounts)]
+            #[instruction(bump: u8, name: String)]
+            pub struct Create<'info> {
+                pub payer: Signer<'info>,
+            }
+        };
+
+        let account = convert_account_struct(&structure).unwrap();
+        let args: Vec<(&str, &str)> = account
+            .instruction_args
+            .iter()
+            .map(|arg| (arg.name.as_str(), arg.ty.as_str()))
+            .collect();
+        assert_eq!(args, vec![("bump", "u8"), ("name", "String")]);
+    }
+
+    #[test]
+    fn test_convert_account_struct_malformed_instruction_args() {
+        let structure: ItemStruct = parse_quote! {
+            #[derive(Accounts)]
+            #[instruction(bump: u8, name String, seeds: BTreeMap<u8, u8>)]
+            pub struct Create<'info> {
+                pub payer: Signer<'info>,
+            }
+        };
+
+        // The malformed argument is skipped; the struct still converts
+        let account = convert_account_struct(&structure).unwrap();
+        let args: Vec<(&str, &str)> = account
+            .instruction_args
+            .iter()
+            .map(|arg| (arg.name.as_str(), arg.ty.as_str()))
+            .collect();
+        assert_eq!(args, vec![("bump", "u8"), ("seeds", "BTreeMap<u8,u8>")]);
+        assert_eq!(account.fields.len(), 1);
+    }
+
     #[test]
     fn test_convert_raw_account() {
         // Create a raw account struct with syn
@@ -435,6 +1507,7 @@ mod tests {
         // Verify the result
         assert_eq!(raw_account.name, "UserData");
         assert_eq!(raw_account.visibility, "pub");
+        assert!(!raw_account.derives_init_space);
         assert_eq!(raw_account.fields.len(), 3);
 
         // Check fields
@@ -448,6 +1521,66 @@ mod tests {
         assert_eq!(created_field.visibility, ""); // Not public
     }
 
+    #[test]
+    fn test_convert_raw_account_discriminator_override() {
+        let raw_account_struct = parse_quote! {
+            #[account(discriminator = [9, 9, 9, 9])]
+            pub struct Custom {
+                pub value: u64,
+            }
+        };
+        let raw_account = convert_raw_account(&raw_account_struct).unwrap();
+        assert_eq!(raw_account.discriminator_override, Some(vec![9, 9, 9, 9]));
+
+        let raw_account_struct = parse_quote! {
+            #[account(discriminator = 1)]
+            pub struct Single {
+                pub value: u64,
+            }
+        };
+        let raw_account = convert_raw_account(&raw_account_struct).unwrap();
+        assert_eq!(raw_account.discriminator_override, Some(vec![1]));
+
+        let raw_account_struct = parse_quote! {
+            #[account]
+            pub struct Plain {
+                pub value: u64,
+            }
+        };
+        let raw_account = convert_raw_account(&raw_account_struct).unwrap();
+        assert_eq!(raw_account.discriminator_override, None);
+    }
+
+    #[test]
+    fn test_convert_raw_account_max_len() {
+        let raw_account_struct = parse_quote! {
+            #[account]
+            #[derive(InitSpace)]
+            pub struct Profile {
+                /// Display name
+                #[max_len(50)]
+                pub name: String,
+                #[max_len(10, 32)]
+                pub tags: Vec<String>,
+                pub owner: Pubkey,
+            }
+        };
+
+        let raw_account = convert_raw_account(&raw_account_struct).unwrap();
+        assert!(raw_account.derives_init_space);
+
+        let name_field = raw_account.find_field("name").unwrap();
+        assert_eq!(name_field.max_len, vec![50]);
+        assert_eq!(name_field.attributes, vec!["max_len(50)"]);
+
+        let tags_field = raw_account.find_field("tags").unwrap();
+        assert_eq!(tags_field.max_len, vec![10, 32]);
+
+        let owner_field = raw_account.find_field("owner").unwrap();
+        assert!(owner_field.max_len.is_empty());
+        assert!(owner_field.attributes.is_empty());
+    }
+
     #[test]
     fn test_format_visibility() {
         let public: Visibility = parse_quote!(pub);
@@ -463,13 +1596,53 @@ mod tests {
     #[test]
     fn test_format_type() {
         let simple_type: syn::Type = parse_quote!(u64);
-        assert_eq!(format_type(&simple_type), "u64");
+        assert_eq!(format_type(&simple_type).unwrap(), "u64");
 
         let generic_type: syn::Type = parse_quote!(Option<String>);
-        assert_eq!(format_type(&generic_type), "Option<String>");
+        assert_eq!(format_type(&generic_type).unwrap(), "Option<String>");
 
         let complex_type: syn::Type = parse_quote!(HashMap<Pubkey, Vec<u8>>);
-        assert_eq!(format_type(&complex_type), "HashMap<Pubkey,Vec<u8>>");
+        assert_eq!(
+            format_type(&complex_type).unwrap(),
+            "HashMap<Pubkey,Vec<u8>>"
+        );
+    }
+
+    #[test]
+    fn test_format_type_nesting_limit() {
+        // Build the type directly, since parsing it would exhaust syn's
+        // own recursion first
+        let nested = |depth: usize| {
+            let mut ty: syn::Type = parse_quote!(u64);
+            for _ in 0..depth {
+                let mut wrapper: syn::Type = parse_quote!(Option<u64>);
+                if let syn::Type::Path(type_path) = &mut wrapper {
+                    if let syn::PathArguments::AngleBracketed(args) =
+                        &mut type_path.path.segments[0].arguments
+                    {
+                        args.args[0] = syn::GenericArgument::Type(ty);
+                    }
+                }
+                ty = wrapper;
+            }
+            ty
+        };
+
+        assert!(format_type(&nested(MAX_TYPE_DEPTH - 1)).is_ok());
+
+        let result = format_type(&nested(200));
+        assert!(matches!(result, Err(ParseError::LimitExceeded(_))));
+
+        let mut structure: ItemStruct = parse_quote! {
+            #[account]
+            pub struct Deep {
+                pub value: u64,
+            }
+        };
+        if let syn::Fields::Named(fields) = &mut structure.fields {
+            fields.named[0].ty = nested(200);
+        }
+        assert!(convert_raw_account(&structure).is_err());
     }
 
     #[test]
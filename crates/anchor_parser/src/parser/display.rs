@@ -0,0 +1,156 @@
+//! Outline display of a parsed syntax tree
+//!
+//! Renders the top-level items of a file one per line, with the items of
+//! inline modules indented below them. Handy for seeing what the parser
+//! will pick up from a file.
+
+use crate::parser::convert::format_use;
+use crate::parser::predicates;
+use std::fmt::Write;
+use syn::{File, Item};
+use utils::fmt::indent;
+
+/// Format every item of a syntax tree as an outline
+///
+/// # Arguments
+///
+/// * `file` - The parsed syntax tree
+///
+/// # Returns
+///
+/// One line per item, e.g. `fn helper` or `#[program] mod vault`
+pub fn format_ast(file: &File) -> String {
+    let mut out = String::new();
+    for item in &file.items {
+        write_item(&mut out, item, 0);
+    }
+    out
+}
+
+/// Format only the Anchor items of a syntax tree as an outline
+///
+/// Keeps `#[program]` modules, `#[derive(Accounts)]` structs and
+/// `#[account]` structs, dropping helper functions, uses and other items
+/// at the top level. Program modules are shown with all their items.
+pub fn format_ast_anchor_only(file: &File) -> String {
+    let mut out = String::new();
+    for item in file.items.iter().filter(|item| is_anchor_item(item)) {
+        write_item(&mut out, item, 0);
+    }
+    out
+}
+
+/// Check whether a top-level item is one the converter turns into a model item
+fn is_anchor_item(item: &Item) -> bool {
+    match item {
+        Item::Mod(module) => predicates::is_anchor_program(module),
+        Item::Struct(structure) => {
+            predicates::is_account_struct(structure) || predicates::is_raw_account(structure)
+        }
+        _ => false,
+    }
+}
+
+/// Write one item line, recursing into inline module contents
+fn write_item(out: &mut String, item: &Item, level: usize) {
+    let line = match item {
+        Item::Mod(module) if predicates::is_anchor_program(module) => {
+            format!("#[program] mod {}", module.ident)
+        }
+        Item::Mod(module) => format!("mod {}", module.ident),
+        Item::Fn(function) => format!("fn {}", function.sig.ident),
+        Item::Struct(structure) if predicates::is_account_struct(structure) => {
+            format!("#[derive(Accounts)] struct {}", structure.ident)
+        }
+        Item::Struct(structure) if predicates::is_raw_account(structure) => {
+            format!("#[account] struct {}", structure.ident)
+        }
+        Item::Struct(structure) => format!("struct {}", structure.ident),
+        Item::Enum(item_enum) => format!("enum {}", item_enum.ident),
+        Item::Use(item_use) => format!("use {}", format_use(item_use)),
+        Item::Const(item_const) => format!("const {}", item_const.ident),
+        Item::Impl(_) => "impl".to_string(),
+        Item::Macro(item_macro) => format!(
+            "{}!",
+            item_macro
+                .mac
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string())
+                .unwrap_or_default()
+        ),
+        _ => "item".to_string(),
+    };
+    writeln!(out, "{}{}", indent(level), line).unwrap();
+
+    if let Item::Mod(module) = item {
+        for nested in module.content.iter().flat_map(|(_, items)| items) {
+            write_item(out, nested, level + 1);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn example() -> File {
+        parse_quote! {
+            use anchor_lang::prelude::*;
+
+            declare_id!("11111111111111111111111111111111");
+
+            #[program]
+            pub mod vault {
+                use super::*;
+
+                pub fn deposit(ctx: Context<Deposit>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            fn stray_helper() -> u64 {
+                42
+            }
+
+            #[derive(Accounts)]
+            pub struct Deposit<'info> {
+                pub authority: Signer<'info>,
+            }
+
+            #[account]
+            pub struct Vault {
+                pub amount: u64,
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_ast() {
+        assert_eq!(
+            format_ast(&example()),
+            "use anchor_lang::prelude::*\n\
+             declare_id!\n\
+             #[program] mod vault\n  use super::*\n  fn deposit\n\
+             fn stray_helper\n\
+             #[derive(Accounts)] struct Deposit\n\
+             #[account] struct Vault\n"
+        );
+    }
+
+    #[test]
+    fn test_format_ast_anchor_only() {
+        let display = format_ast_anchor_only(&example());
+
+        assert!(!display.contains("stray_helper"));
+        assert!(!display.contains("declare_id"));
+        assert_eq!(
+            display,
+            "#[program] mod vault\n  use super::*\n  fn deposit\n\
+             #[derive(Accounts)] struct Deposit\n\
+             #[account] struct Vault\n"
+        );
+    }
+}
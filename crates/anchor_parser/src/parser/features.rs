@@ -0,0 +1,138 @@
+//! Enabled cfg options for parsing
+//!
+//! Lets callers pick which `#[cfg(...)]`-gated program modules are kept,
+//! mirroring the feature set a program would be compiled with.
+
+use crate::model::{CfgPredicate, ProgramModule};
+
+/// The cfg options considered enabled while parsing
+///
+/// The default enables nothing, so any module gated on an option is
+/// skipped while `not(...)` gates hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseFeatures {
+    /// Enabled options, as `(name, value)` pairs
+    pub options: Vec<(String, Option<String>)>,
+}
+
+impl ParseFeatures {
+    /// Create a feature set with nothing enabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method: with a Cargo feature (`feature = "name"`) enabled
+    pub fn with_feature(self, name: impl Into<String>) -> Self {
+        self.with_option("feature", Some(name.into()))
+    }
+
+    /// Builder method: with a bare cfg flag (e.g., `test`) enabled
+    pub fn with_flag(self, name: impl Into<String>) -> Self {
+        self.with_option(name, None)
+    }
+
+    /// Builder method: with an arbitrary cfg option enabled
+    pub fn with_option(mut self, name: impl Into<String>, value: Option<String>) -> Self {
+        self.options.push((name.into(), value));
+        self
+    }
+
+    /// Whether an option is enabled
+    pub fn is_set(&self, name: &str, value: Option<&str>) -> bool {
+        self.options
+            .iter()
+            .any(|(n, v)| n == name && v.as_deref() == value)
+    }
+
+    /// Whether a predicate holds under this feature set
+    pub fn satisfies(&self, predicate: &CfgPredicate) -> bool {
+        predicate.evaluate(&|name, value| self.is_set(name, value))
+    }
+
+    /// Whether a program module would be compiled under this feature set
+    pub fn includes(&self, module: &ProgramModule) -> bool {
+        module.cfg_satisfied(&|name, value| self.is_set(name, value))
+    }
+}
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use super::*;
+    use crate::parser::parse_str_with;
+
+    #[test]
+    fn test_parse_str_with_selects_cfg_modules() {
+        let source = r#"
+            #[cfg(feature = "devnet")]
+            #[program]
+            pub mod devnet_program {
+                use super::*;
+                pub fn airdrop(ctx: Context<Airdrop>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[cfg(not(feature = "devnet"))]
+            #[program]
+            pub mod mainnet_program {
+                use super::*;
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+        "#;
+
+        let names = |program: &crate::Program| -> Vec<String> {
+            program
+                .program_modules
+                .iter()
+                .map(|m| m.name.clone())
+                .collect()
+        };
+
+        let devnet = parse_str_with(source, &ParseFeatures::new().with_feature("devnet")).unwrap();
+        assert_eq!(names(&devnet), ["devnet_program"]);
+        assert_eq!(devnet.item_order.len(), 1);
+
+        let mainnet = parse_str_with(source, &ParseFeatures::new()).unwrap();
+        assert_eq!(names(&mainnet), ["mainnet_program"]);
+        assert_eq!(
+            mainnet.program_modules[0].cfg,
+            [CfgPredicate::Not(Box::new(CfgPredicate::Option {
+                name: "feature".to_string(),
+                value: Some("devnet".to_string()),
+            }))]
+        );
+
+        // Unfiltered parsing keeps both modules with their predicates
+        let all = crate::parse_str(source).unwrap();
+        assert_eq!(names(&all), ["devnet_program", "mainnet_program"]);
+    }
+
+    #[test]
+    fn test_satisfies_nested_predicates() {
+        let predicate = CfgPredicate::All(vec![
+            CfgPredicate::Option {
+                name: "test".to_string(),
+                value: None,
+            },
+            CfgPredicate::Any(vec![
+                CfgPredicate::Option {
+                    name: "feature".to_string(),
+                    value: Some("a".to_string()),
+                },
+                CfgPredicate::Option {
+                    name: "feature".to_string(),
+                    value: Some("b".to_string()),
+                },
+            ]),
+        ]);
+
+        assert!(ParseFeatures::new()
+            .with_flag("test")
+            .with_feature("b")
+            .satisfies(&predicate));
+        assert!(!ParseFeatures::new().with_feature("b").satisfies(&predicate));
+        assert!(!ParseFeatures::new().with_flag("test").satisfies(&predicate));
+    }
+}
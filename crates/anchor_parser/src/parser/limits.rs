@@ -0,0 +1,156 @@
+//! Resource limits for parsing
+//!
+//! Guards against huge or deeply nested inputs, which could otherwise
+//! exhaust memory or the stack of a service that parses untrusted code.
+
+use crate::error::{ParseError, Result};
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Limits applied before parsing
+///
+/// The default applies no limits, matching [`parse_file`](crate::parse_file).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum source size in bytes
+    pub max_file_size: Option<u64>,
+
+    /// Maximum nesting depth of `()`, `[]` and `{}` delimiters
+    pub max_nesting_depth: Option<usize>,
+}
+
+impl ParseLimits {
+    /// Create limits with no restrictions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder method: with a maximum source size in bytes
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Builder method: with a maximum delimiter nesting depth
+    pub fn with_max_nesting_depth(mut self, depth: usize) -> Self {
+        self.max_nesting_depth = Some(depth);
+        self
+    }
+
+    /// Check a source size against the size limit
+    pub fn check_size(&self, size: u64) -> Result<()> {
+        match self.max_file_size {
+            Some(max) if size > max => Err(ParseError::LimitExceeded(format!(
+                "source is {} bytes, maximum is {}",
+                size, max
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check source text against all limits
+    pub fn check_source(&self, source: &str) -> Result<()> {
+        self.check_size(source.len() as u64)?;
+
+        if let Some(max) = self.max_nesting_depth {
+            let depth = nesting_depth(source);
+            if depth > max {
+                return Err(ParseError::LimitExceeded(format!(
+                    "nesting depth is {}, maximum is {}",
+                    depth, max
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compute the maximum delimiter nesting depth of source text
+///
+/// Depth is taken from the group tree of the lexed tokens, so delimiters
+/// inside string, raw string and char literals and comments are ignored.
+/// The lexer and this walk are iterative, so neither is bounded by the
+/// stack. Source that fails to lex has depth 0 and is left to the parser
+/// to reject.
+fn nesting_depth(source: &str) -> usize {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return 0;
+    };
+
+    let mut max_depth = 0usize;
+    let mut stack = vec![tokens.into_iter()];
+
+    while let Some(iter) = stack.last_mut() {
+        match iter.next() {
+            Some(TokenTree::Group(group)) => {
+                stack.push(group.stream().into_iter());
+                max_depth = max_depth.max(stack.len() - 1);
+            }
+            Some(_) => {}
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    max_depth
+}
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_file_with_limits, parse_str_with_limits};
+    use std::fs;
+
+    #[test]
+    fn test_default_limits_are_unbounded() {
+        let limits = ParseLimits::default();
+        assert!(limits.check_source(&"{".repeat(1000)).is_ok());
+    }
+
+    #[test]
+    fn test_file_size_limit() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("large.rs");
+        let source = "pub struct Filler {}\n".repeat(100);
+        fs::write(&path, &source).unwrap();
+
+        let limits = ParseLimits::new().with_max_file_size(64);
+        let result = parse_file_with_limits(&path, &limits);
+        assert!(matches!(result, Err(ParseError::LimitExceeded(_))));
+
+        let limits = ParseLimits::new().with_max_file_size(source.len() as u64);
+        assert!(parse_file_with_limits(&path, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_nesting_depth_limit() {
+        let source = "fn deep() { { { { let s = \"{{{{\"; } } } }";
+        assert_eq!(nesting_depth(source), 4);
+
+        let limits = ParseLimits::new().with_max_nesting_depth(3);
+        let result = parse_str_with_limits(source, &limits);
+        assert!(matches!(result, Err(ParseError::LimitExceeded(_))));
+
+        let limits = ParseLimits::new().with_max_nesting_depth(4);
+        assert!(parse_str_with_limits(source, &limits).is_ok());
+    }
+
+    #[test]
+    fn test_nesting_depth_ignores_literals() {
+        // Delimiters in char literals don't count, and lifetimes aren't chars
+        assert_eq!(nesting_depth("fn f<'a>(x: &'a str) { let c = '('; }"), 1);
+        assert_eq!(nesting_depth("fn f() { let b = b'['; }"), 1);
+
+        // A quote in a char literal doesn't hide the rest of the source
+        let source = "fn f() { let q = '\"'; { { { } } } }";
+        assert_eq!(nesting_depth(source), 4);
+        let limits = ParseLimits::new().with_max_nesting_depth(3);
+        let result = parse_str_with_limits(source, &limits);
+        assert!(matches!(result, Err(ParseError::LimitExceeded(_))));
+
+        // Raw strings may contain quotes and delimiters
+        let source = r###"fn f() { let s = r#"(" {{ "#; { } }"###;
+        assert_eq!(nesting_depth(source), 2);
+    }
+}
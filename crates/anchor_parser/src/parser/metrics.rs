@@ -0,0 +1,63 @@
+//! Parse metrics for profiling
+//!
+//! Records how long each parsing stage takes and how much input was
+//! processed, so slow stages can be spotted on large files.
+
+use std::time::Duration;
+
+/// Timings and counts collected while parsing a source file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseMetrics {
+    /// Time spent parsing the source into a `syn` syntax tree
+    pub syn_duration: Duration,
+
+    /// Time spent converting the syntax tree into the Program model
+    pub convert_duration: Duration,
+
+    /// Number of top-level items processed
+    pub item_count: usize,
+}
+
+impl ParseMetrics {
+    /// Total time spent across all stages
+    pub fn total_duration(&self) -> Duration {
+        self.syn_duration + self.convert_duration
+    }
+}
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use crate::parser::parse_str_timed;
+
+    #[test]
+    fn test_parse_str_timed() {
+        let source = r#"
+            use anchor_lang::prelude::*;
+
+            declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+            #[program]
+            pub mod hello_world {
+                use super::*;
+
+                pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+                    Ok(())
+                }
+            }
+
+            #[derive(Accounts)]
+            pub struct Initialize {}
+        "#;
+
+        let (program, metrics) = parse_str_timed(source).unwrap();
+
+        assert_eq!(program.program_modules.len(), 1);
+        assert_eq!(metrics.item_count, 4);
+        assert!(metrics.syn_duration > std::time::Duration::ZERO);
+        assert!(metrics.convert_duration > std::time::Duration::ZERO);
+        assert_eq!(
+            metrics.total_duration(),
+            metrics.syn_duration + metrics.convert_duration
+        );
+    }
+}
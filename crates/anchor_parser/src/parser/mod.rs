@@ -1,10 +1,21 @@
 pub mod convert;
+pub mod display;
+pub mod features;
+pub mod limits;
+pub mod metrics;
 mod predicates;
+mod render;
 
 use crate::error::{ParseError, Result};
 use crate::model::program::Program;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub use display::{format_ast, format_ast_anchor_only};
+pub use features::ParseFeatures;
+pub use limits::ParseLimits;
+pub use metrics::ParseMetrics;
 
 /// Parse an Anchor program file into a Program model
 pub fn parse_file(path: &Path) -> Result<Program> {
@@ -12,6 +23,54 @@ pub fn parse_file(path: &Path) -> Result<Program> {
     parse_str(&source)
 }
 
+/// Parse every `.rs` file below a directory
+///
+/// Files are visited recursively in path order and parsed lazily, so a
+/// caller can stop at the first failure or keep going and collect every
+/// per-file result. The iterator's length is the number of files found.
+/// Fails only if the directory tree can't be read.
+pub fn parse_directory(
+    dir: &Path,
+) -> Result<impl ExactSizeIterator<Item = (PathBuf, Result<Program>)>> {
+    let mut files = Vec::new();
+    collect_rust_files(dir, &mut files)?;
+    files.sort();
+
+    Ok(files.into_iter().map(|path| {
+        let result = parse_file(&path);
+        (path, result)
+    }))
+}
+
+/// Recursively collect the `.rs` files below a directory
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_rust_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parse an Anchor program file, refusing inputs that exceed the given limits
+///
+/// The file size is checked before the file is read, and the nesting depth
+/// before parsing, so oversized inputs fail with [`ParseError::LimitExceeded`].
+pub fn parse_file_with_limits(path: &Path, limits: &ParseLimits) -> Result<Program> {
+    limits.check_size(fs::metadata(path)?.len())?;
+    let source = fs::read_to_string(path)?;
+    parse_str_with_limits(&source, limits)
+}
+
+/// Parse Anchor program source code, refusing inputs that exceed the given limits
+pub fn parse_str_with_limits(source: &str, limits: &ParseLimits) -> Result<Program> {
+    limits.check_source(source)?;
+    parse_str(source)
+}
+
 /// Parse Anchor program source code into a Program model
 pub fn parse_str(source: &str) -> Result<Program> {
     // First, parse with syn
@@ -21,5 +80,74 @@ pub fn parse_str(source: &str) -> Result<Program> {
     convert::convert_file(&file)
 }
 
+/// Parse Anchor program source code, keeping only the program modules whose
+/// `#[cfg(...)]` predicates hold under the given feature set
+pub fn parse_str_with(source: &str, features: &ParseFeatures) -> Result<Program> {
+    let file = syn::parse_str::<syn::File>(source).map_err(ParseError::Syntax)?;
+
+    let options = convert::ConvertOptions {
+        features: Some(features.clone()),
+        ..convert::ConvertOptions::default()
+    };
+    convert::convert_file_filtered(&file, &options)
+}
+
+/// Parse Anchor program source code, recording timings for each stage
+///
+/// Behaves like [`parse_str`] but also returns [`ParseMetrics`] with the
+/// `syn` parse and conversion durations and the number of items processed.
+pub fn parse_str_timed(source: &str) -> Result<(Program, ParseMetrics)> {
+    let start = Instant::now();
+    let file = syn::parse_str::<syn::File>(source).map_err(ParseError::Syntax)?;
+    let syn_duration = start.elapsed();
+
+    let start = Instant::now();
+    let program = convert::convert_file(&file)?;
+    let convert_duration = start.elapsed();
+
+    let metrics = ParseMetrics {
+        syn_duration,
+        convert_duration,
+        item_count: file.items.len(),
+    };
+
+    Ok((program, metrics))
+}
+
 // Re-export for compatibility with existing code
 pub use predicates::{is_anchor_instruction, is_anchor_program};
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("b_good.rs"), "pub struct A {}").unwrap();
+        fs::write(dir.path().join("a_bad.rs"), "pub struct {").unwrap();
+        fs::write(dir.path().join("nested/c_good.rs"), "pub struct C {}").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not rust").unwrap();
+
+        let results: Vec<_> = parse_directory(dir.path()).unwrap().collect();
+
+        let names: Vec<_> = results
+            .iter()
+            .map(|(path, _)| path.strip_prefix(dir.path()).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                PathBuf::from("a_bad.rs"),
+                PathBuf::from("b_good.rs"),
+                PathBuf::from("nested/c_good.rs"),
+            ]
+        );
+        assert!(results[0].1.is_err());
+        assert!(results[1].1.is_ok());
+        assert!(results[2].1.is_ok());
+
+        assert!(parse_directory(&dir.path().join("missing")).is_err());
+    }
+}
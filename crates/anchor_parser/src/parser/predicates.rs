@@ -4,7 +4,7 @@
 //! an Anchor-specific construct like a program module, instruction,
 //! or account structure.
 
-use syn::{ItemFn, ItemMod, ItemStruct, Type, TypePath};
+use syn::{ItemEnum, ItemFn, ItemMod, ItemStruct, Type, TypePath};
 
 /// Determines if a module is an Anchor program module
 ///
@@ -81,6 +81,20 @@ fn is_context_path(type_path: &TypePath) -> bool {
 ///
 /// `true` if the struct has #[derive(Accounts)]
 pub fn is_account_struct(structure: &ItemStruct) -> bool {
+    has_derive(structure, "Accounts")
+}
+
+/// Determines if a struct derives a trait with the given name
+///
+/// # Arguments
+///
+/// * `structure` - The struct to check
+/// * `name` - The derive name (e.g., "InitSpace")
+///
+/// # Returns
+///
+/// `true` if any #[derive(...)] attribute lists `name`
+pub fn has_derive(structure: &ItemStruct, name: &str) -> bool {
     structure.attrs.iter().any(|attr| {
         if !attr.path().is_ident("derive") {
             return false;
@@ -90,9 +104,9 @@ pub fn is_account_struct(structure: &ItemStruct) -> bool {
         attr.parse_args_with(|content: syn::parse::ParseStream| {
             let derives = content.parse_terminated(syn::Path::parse_mod_style, syn::Token![,])?;
 
-            // Check if Accounts is in the derive list
+            // Check if the name is in the derive list
             for path in derives {
-                if path.is_ident("Accounts") {
+                if path.is_ident(name) {
                     return Ok(true);
                 }
             }
@@ -122,6 +136,23 @@ pub fn is_raw_account(structure: &ItemStruct) -> bool {
         .any(|attr| attr.path().is_ident("account"))
 }
 
+/// Determines if an enum defines custom program errors
+///
+/// In Anchor, error enums are marked with the #[error_code] attribute.
+///
+/// # Arguments
+///
+/// * `item` - The enum to check
+///
+/// # Returns
+///
+/// `true` if the enum has the #[error_code] attribute
+pub fn is_error_enum(item: &ItemEnum) -> bool {
+    item.attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("error_code"))
+}
+
 #[cfg(all(test, feature = "unit_test"))]
 mod tests {
     use super::*;
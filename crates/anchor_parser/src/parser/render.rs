@@ -0,0 +1,250 @@
+//! Token rendering with source-like spacing
+//!
+//! Turns token streams back into text spaced the way rustfmt writes it
+//! (`[a, b, c]`, `x.len() >= 2`, `where T: Owner + Clone`), so stored
+//! values read like the source without keeping source locations around.
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Operators made of several punctuation characters
+const MULTI_CHAR_OPS: &[&str] = &[
+    "..=", "<<=", ">>=", "...", "::", "==", "!=", "<=", ">=", "&&", "||", "->", "=>", "..", "+=",
+    "-=", "*=", "/=", "%=", "^=", "&=", "|=", "<<", ">>",
+];
+
+/// Operators written without spaces around them
+const TIGHT_OPS: &[&str] = &[".", "::", "..", "..=", "..."];
+
+/// Operators that are prefix (unary) when they start an operand
+const PREFIX_OPS: &[&str] = &["&", "&&", "*", "-", "!"];
+
+/// Render tokens as expression text
+pub(crate) fn render_expr(tokens: impl IntoIterator<Item = TokenTree>) -> String {
+    render(tokens, false)
+}
+
+/// Render tokens as type text, where every `<` opens generic arguments
+pub(crate) fn render_type(tokens: impl IntoIterator<Item = TokenTree>) -> String {
+    render(tokens, true)
+}
+
+fn render(tokens: impl IntoIterator<Item = TokenTree>, type_context: bool) -> String {
+    let mut renderer = Renderer::new(type_context);
+    renderer.push_stream(tokens.into_iter().collect());
+    renderer.out
+}
+
+/// What the last rendered token was, deciding the space before the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    /// Nothing yet, or just after an opening delimiter
+    Start,
+
+    /// An identifier, keyword or literal
+    Ident,
+
+    /// A closing delimiter, `?` or the `>` closing generic arguments
+    Close,
+
+    /// An operator that takes a space after it (`+`, `==`, `,`, `:`)
+    Spaced,
+
+    /// An operator the next token is attached to (`.`, `::`, prefix `&`)
+    Tight,
+}
+
+struct Renderer {
+    out: String,
+    last: Last,
+    type_context: bool,
+    /// Open generic argument lists
+    generics: usize,
+    /// Inside closure parameters `|...|`
+    closure_params: bool,
+}
+
+impl Renderer {
+    fn new(type_context: bool) -> Self {
+        Self {
+            out: String::new(),
+            last: Last::Start,
+            type_context,
+            generics: 0,
+            closure_params: false,
+        }
+    }
+
+    fn push_stream(&mut self, stream: TokenStream) {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i] {
+                TokenTree::Ident(ident) => self.push_word(&ident.to_string()),
+                TokenTree::Literal(literal) => self.push_word(&literal.to_string()),
+                TokenTree::Group(group) => {
+                    // Calls and indexing attach to what they apply to
+                    let attached = matches!(self.last, Last::Ident | Last::Close)
+                        && group.delimiter() != Delimiter::Brace;
+                    if !attached {
+                        self.space();
+                    }
+
+                    // Generic and closure state doesn't cross delimiters
+                    let mut inner = Renderer::new(self.type_context);
+                    inner.push_stream(group.stream());
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::Brace if inner.out.is_empty() => ("{", "}"),
+                        Delimiter::Brace => ("{ ", " }"),
+                        Delimiter::None => ("", ""),
+                    };
+                    self.out.push_str(open);
+                    self.out.push_str(&inner.out);
+                    self.out.push_str(close);
+                    self.last = Last::Close;
+                }
+                TokenTree::Punct(_) => {
+                    let (op, len) = self.operator(&tokens[i..]);
+                    self.push_op(&op, tokens.get(i + len));
+                    i += len;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+    }
+
+    /// Read the operator at the start of `tokens`, returning it and its
+    /// length in tokens
+    fn operator(&self, tokens: &[TokenTree]) -> (String, usize) {
+        let mut joined = String::new();
+        for token in tokens {
+            let TokenTree::Punct(punct) = token else {
+                break;
+            };
+            joined.push(punct.as_char());
+            if punct.spacing() == Spacing::Alone {
+                break;
+            }
+        }
+
+        let longest = MULTI_CHAR_OPS
+            .iter()
+            .filter(|op| joined.starts_with(**op))
+            // `>>` closes two generic lists when inside one
+            .filter(|op| !(self.generics > 0 && op.starts_with(['<', '>'])))
+            .map(|op| op.len())
+            .max()
+            .unwrap_or(1);
+        (joined[..longest].to_string(), longest)
+    }
+
+    fn push_op(&mut self, op: &str, next: Option<&TokenTree>) {
+        let operand_position = matches!(self.last, Last::Start | Last::Spaced | Last::Tight);
+
+        let (space_before, last) = match op {
+            "," | ";" | ":" => (false, Last::Spaced),
+            "?" => (false, Last::Close),
+            // Lifetimes lex as a `'` before their name
+            "'" | "#" => (true, Last::Tight),
+            // Macro invocations: `msg!(...)`, `vec![...]`
+            "!" if self.last == Last::Ident && matches!(next, Some(TokenTree::Group(_))) => {
+                (false, Last::Tight)
+            }
+            "<" if self.type_context || self.generics > 0 || self.out.ends_with("::") => {
+                self.generics += 1;
+                (false, Last::Tight)
+            }
+            ">" if self.generics > 0 => {
+                self.generics -= 1;
+                (false, Last::Close)
+            }
+            "|" if self.closure_params => {
+                self.closure_params = false;
+                (false, Last::Spaced)
+            }
+            "|" if operand_position => {
+                self.closure_params = true;
+                (true, Last::Tight)
+            }
+            "||" if operand_position => (true, Last::Spaced),
+            op if TIGHT_OPS.contains(&op) => (false, Last::Tight),
+            op if operand_position && PREFIX_OPS.contains(&op) => (true, Last::Tight),
+            _ => {
+                if self.last != Last::Start {
+                    self.out.push(' ');
+                }
+                (false, Last::Spaced)
+            }
+        };
+
+        if space_before {
+            self.space();
+        }
+        self.out.push_str(op);
+        self.last = last;
+    }
+
+    fn push_word(&mut self, word: &str) {
+        self.space();
+        self.out.push_str(word);
+        self.last = Last::Ident;
+    }
+
+    /// Add a space unless the last token binds to the next one
+    fn space(&mut self) {
+        if matches!(self.last, Last::Ident | Last::Close | Last::Spaced) {
+            self.out.push(' ');
+        }
+    }
+}
+
+#[cfg(all(test, feature = "unit_test"))]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn expr(tokens: TokenStream) -> String {
+        render_expr(tokens)
+    }
+
+    #[test]
+    fn test_render_expr_spacing() {
+        assert_eq!(expr(quote!([a, b, c])), "[a, b, c]");
+        assert_eq!(expr(quote!(x.len() >= 2)), "x.len() >= 2");
+        assert_eq!(expr(quote!(vault.name == "a,b")), r#"vault.name == "a,b""#);
+        assert_eq!(
+            expr(quote!(8 + std::mem::size_of::<Vault>())),
+            "8 + std::mem::size_of::<Vault>()"
+        );
+        assert_eq!(
+            expr(quote!([b"v", user.key().as_ref(), &[bump]])),
+            r#"[b"v", user.key().as_ref(), &[bump]]"#
+        );
+        assert_eq!(
+            expr(quote!(a < b && !flag @ ErrorCode::Bad)),
+            "a < b && !flag @ ErrorCode::Bad"
+        );
+        assert_eq!(expr(quote!(-1 * (x - 2))), "-1 * (x - 2)");
+        assert_eq!(expr(quote!(x.checked_add(1)?)), "x.checked_add(1)?");
+        assert_eq!(
+            expr(quote!(list.iter().all(|a| a.is_signer))),
+            "list.iter().all(|a| a.is_signer)"
+        );
+        assert_eq!(expr(quote!(vec![1u8; 4].len())), "vec![1u8; 4].len()");
+        assert_eq!(expr(quote!(Vec::<Vec<u8>>::new())), "Vec::<Vec<u8>>::new()");
+    }
+
+    #[test]
+    fn test_render_type_spacing() {
+        assert_eq!(
+            render_type(quote!(where T: Owner + Clone,)),
+            "where T: Owner + Clone,"
+        );
+        assert_eq!(
+            render_type(quote!(where T: Into<Vec<u8>>, 'info: 'a)),
+            "where T: Into<Vec<u8>>, 'info: 'a"
+        );
+    }
+}
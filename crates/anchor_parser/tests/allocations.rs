@@ -0,0 +1,140 @@
+//! Allocation regression test for syntax conversion
+//!
+//! Runs in its own test binary so the counting allocator only sees this
+//! test's allocations.
+
+#[cfg(all(test, feature = "module_test"))]
+mod allocation_tests {
+    use anchor_parser::parser::convert::convert_file;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Wraps the system allocator, tracking live and peak heap bytes
+    struct CountingAllocator;
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            let live = LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    /// A generated file with many account structs and raw accounts
+    fn large_source(count: usize) -> String {
+        let mut source = String::from("#[program]\npub mod generated {\n    use super::*;\n");
+        for i in 0..count {
+            source.push_str(&format!(
+                "    pub fn op_{i}(ctx: Context<Op{i}>, amount: u64) -> Result<()> {{ Ok(()) }}\n"
+            ));
+        }
+        source.push_str("}\n");
+
+        for i in 0..count {
+            source.push_str(&format!(
+                r#"
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct Op{i}<'info> {{
+    #[account(mut, seeds = [b"state", authority.key().as_ref()], bump, has_one = authority)]
+    pub state: Account<'info, State{i}>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}}
+
+#[account]
+#[derive(InitSpace)]
+pub struct State{i} {{
+    pub authority: Pubkey,
+    #[max_len(32)]
+    pub label: String,
+    pub amount: u64,
+}}
+"#
+            ));
+        }
+
+        source
+    }
+
+    /// Heap usage of one measured operation
+    #[derive(Debug)]
+    struct Usage {
+        allocations: usize,
+        peak: usize,
+        retained: usize,
+    }
+
+    /// Run `f`, measuring the heap usage of everything it does
+    fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+        let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+        let live_before = LIVE_BYTES.load(Ordering::Relaxed);
+        PEAK_BYTES.store(live_before, Ordering::Relaxed);
+
+        let value = f();
+
+        let usage = Usage {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations_before,
+            peak: PEAK_BYTES.load(Ordering::Relaxed) - live_before,
+            retained: LIVE_BYTES.load(Ordering::Relaxed) - live_before,
+        };
+        (value, usage)
+    }
+
+    #[test]
+    fn test_convert_allocations() {
+        let source = large_source(300);
+
+        // syn's parse of the same source is the baseline: conversion reads
+        // that tree, and should cost well under what building it did
+        let (file, parse) = measure(|| syn::parse_file(&source).unwrap());
+        let (program, convert) = measure(|| convert_file(&file).unwrap());
+
+        // Conversion is still correct
+        assert_eq!(program.program_modules[0].instructions.len(), 300);
+        assert_eq!(program.account_structs.len(), 300);
+        assert_eq!(program.raw_accounts.len(), 300);
+        let state = &program.account_structs[7].fields[0];
+        assert_eq!(state.constraints.len(), 4);
+        assert_eq!(
+            state.find_constraint("seeds").unwrap().value.as_deref(),
+            Some(r#"[b"state", authority.key().as_ref()]"#)
+        );
+        assert_eq!(program.raw_accounts[7].fields[1].max_len, vec![32]);
+
+        // Regression thresholds relative to the parse, with headroom over
+        // the measured ratios (about 1/2 the allocations, 1/10 the bytes)
+        assert!(
+            convert.allocations * 3 < parse.allocations * 2,
+            "{:?} against {:?}",
+            convert,
+            parse
+        );
+        assert!(
+            convert.peak * 5 < parse.peak,
+            "{:?} against {:?}",
+            convert,
+            parse
+        );
+        assert!(
+            convert.retained * 5 < parse.retained,
+            "{:?} against {:?}",
+            convert,
+            parse
+        );
+    }
+}
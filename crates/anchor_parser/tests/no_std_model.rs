@@ -0,0 +1,89 @@
+//! Model construction without the `std` feature
+//!
+//! Run with `cargo test -p anchor_parser --no-default-features` to check the
+//! model builds as `no_std` + `alloc`, and without serde.
+
+use anchor_parser::model::{
+    Account, AccountField, Constraint, Instruction, Parameter, Program, ProgramModule, RawAccount,
+    RawAccountField,
+};
+
+#[test]
+fn test_build_program_without_io() {
+    let instruction = Instruction::new("initialize", "pub")
+        .with_parameter(Parameter::new_context("ctx", "Initialize"))
+        .with_return_type("Result<()>")
+        .with_context_type("Initialize");
+
+    let mut module = ProgramModule::new("counter", "pub");
+    module.add_instruction(instruction);
+
+    let mut counter_field = AccountField::new("counter", "Account<'info, Counter>");
+    counter_field.add_constraint(Constraint::without_value("init"));
+    counter_field.add_constraint(Constraint::with_value("payer", "authority"));
+
+    let mut account = Account::new("Initialize", "pub");
+    account.add_field(counter_field);
+
+    let mut raw_account = RawAccount::new("Counter", "pub");
+    raw_account.add_field(RawAccountField::new("count", "u64", "pub"));
+
+    let program = Program::new()
+        .with_program_module(module)
+        .with_account_struct(account)
+        .with_raw_account(raw_account);
+
+    assert!(program.source_path.is_none());
+    assert_eq!(
+        program
+            .find_program_module("counter")
+            .and_then(|m| m.find_instruction("initialize"))
+            .and_then(|i| i.context_type.as_deref()),
+        Some("Initialize")
+    );
+    assert_eq!(
+        program
+            .find_account_struct("Initialize")
+            .unwrap()
+            .fields
+            .len(),
+        1
+    );
+    assert_eq!(program.find_raw_account("Counter").unwrap().fields.len(), 1);
+}
+
+#[cfg(not(feature = "serde"))]
+#[test]
+fn test_traverse_program_without_serde() {
+    let mut module = ProgramModule::new("vault", "pub");
+    module.add_instruction(
+        Instruction::new("deposit", "pub")
+            .with_parameter(Parameter::new_context("ctx", "Deposit"))
+            .with_parameter(Parameter::new("amount", "u64", false)),
+    );
+
+    let mut account = Account::new("Deposit", "pub");
+    account.add_field(AccountField::new("vault", "Account<'info, Vault>"));
+    account.add_field(AccountField::new("user", "Signer<'info>"));
+
+    let program = Program::new()
+        .with_program_module(module)
+        .with_account_struct(account);
+
+    let parameters: Vec<&str> = program
+        .program_modules
+        .iter()
+        .flat_map(|m| &m.instructions)
+        .flat_map(|i| &i.parameters)
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(parameters, ["ctx", "amount"]);
+
+    let fields: Vec<&str> = program
+        .account_structs
+        .iter()
+        .flat_map(|a| &a.fields)
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(fields, ["vault", "user"]);
+}
@@ -0,0 +1,113 @@
+use super::normalize::{normalize_config, promote_issues, truncate_issues};
+use super::Command;
+use crate::error::Error;
+use anchor_normalizer::model::validation::{IssueSeverity, ValidationIssue, RULES};
+use clap::builder::PossibleValuesParser;
+use clap::{Arg, ArgAction, ArgMatches, Command as ClapCommand};
+use std::path::Path;
+
+pub struct CheckCommand;
+
+impl Command for CheckCommand {
+    fn name(&self) -> &'static str {
+        "check"
+    }
+
+    fn build_subcommand(&self) -> ClapCommand {
+        ClapCommand::new(self.name())
+            .about("Validate Anchor code and report issues")
+            .arg(Arg::new("input").help("Input file to check").required(true))
+            .arg(
+                Arg::new("known-types")
+                    .long("known-types")
+                    .value_name("FILE")
+                    .help("File listing extra known account type names, one per line"),
+            )
+            .arg(
+                Arg::new("warn-unknown-bodies")
+                    .long("warn-unknown-bodies")
+                    .action(ArgAction::SetTrue)
+                    .help("Report instructions whose body could not be analyzed"),
+            )
+            .arg(
+                Arg::new("error-on")
+                    .long("error-on")
+                    .value_name("RULE")
+                    .action(ArgAction::Append)
+                    .value_parser(PossibleValuesParser::new(RULES))
+                    .help("Treat issues from a validation rule as errors (repeatable)"),
+            )
+            .arg(
+                Arg::new("max-issues")
+                    .long("max-issues")
+                    .value_name("N")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("Report at most N issues, most severe first"),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count)
+                    .value_parser(clap::value_parser!(u8))
+                    .help("Increase verbosity"),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .short('q')
+                    .long("quiet")
+                    .help("Suppress all non-essential output")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("verbose"),
+            )
+    }
+
+    fn execute(&self, matches: &ArgMatches) -> Result<(), Error> {
+        let input_path = matches
+            .get_one::<String>("input")
+            .map(Path::new)
+            .ok_or_else(|| Error::MissingArgument("input".to_string()))?;
+
+        // Parse the input file
+        tracing::info!("Parsing file: {:?}", input_path);
+        let program = anchor_parser::parse_file(input_path)?;
+
+        // Build the normalizer configuration
+        let normalize_config = normalize_config(matches)?;
+
+        // Normalize the parsed program, which runs validation
+        tracing::info!("Checking program");
+        let mut normalized_program =
+            anchor_normalizer::normalize_with_config(&program, &normalize_config)?;
+
+        // Count after promotion but before capping, so the summary and exit
+        // status cover every issue
+        promote_issues(&mut normalized_program, matches);
+        let counts = normalized_program.issue_counts();
+        if let Some(&max) = matches.get_one::<usize>("max-issues") {
+            truncate_issues(&mut normalized_program, max);
+        }
+
+        // Report each issue, then the summary footer
+        for issue in &normalized_program.validation_issues {
+            println!("{}", format_issue(issue));
+        }
+        println!("{}", counts);
+
+        if counts.errors > 0 {
+            return Err(Error::CheckFailed(counts.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Format an issue as a single report line (e.g., `warning: message [element]`)
+fn format_issue(issue: &ValidationIssue) -> String {
+    let severity = match issue.severity {
+        IssueSeverity::Error => "error",
+        IssueSeverity::Warning => "warning",
+        IssueSeverity::Info => "info",
+    };
+    format!("{}: {} [{}]", severity, issue.message, issue.element)
+}
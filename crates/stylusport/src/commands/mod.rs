@@ -1,8 +1,10 @@
 use crate::error::Error;
 use clap::{ArgMatches, Command as ClapCommand};
 
+pub mod check;
 pub mod normalize;
 pub mod parse;
+pub mod query;
 // Future command modules
 // pub mod build_ir;
 
@@ -22,6 +24,8 @@ pub fn get_all_commands() -> Vec<Box<dyn Command>> {
     vec![
         Box::new(parse::ParseCommand),
         Box::new(normalize::NormalizeCommand),
+        Box::new(check::CheckCommand),
+        Box::new(query::QueryCommand),
         // Add more commands as they're implemented
         // Box::new(normalize::NormalizeCommand),
     ]
@@ -1,12 +1,16 @@
 use super::Command;
-use crate::config::Config;
+use crate::config::{Config, FORMAT_ENV_VAR};
 use crate::error::Error;
 use crate::output::Displayable;
 use anchor_normalizer;
+use anchor_normalizer::model::validation::{IssueSeverity, ValidationIssue, RULES};
+use anchor_normalizer::NormalizedProgram;
 use anchor_parser;
+use clap::builder::{PossibleValue, PossibleValuesParser};
 use clap::{Arg, ArgAction, ArgMatches, Command as ClapCommand};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
+use std::path::Path;
 
 pub struct NormalizeCommand;
 
@@ -27,7 +31,18 @@ impl Command for NormalizeCommand {
                 Arg::new("format")
                     .long("format")
                     .short('f')
-                    .value_parser(["yaml", "json", "debug"])
+                    .value_parser(PossibleValuesParser::new([
+                        PossibleValue::new("yaml"),
+                        PossibleValue::new("json"),
+                        PossibleValue::new("debug"),
+                        PossibleValue::new("ts"),
+                        PossibleValue::new("matrix-csv"),
+                        PossibleValue::new("matrix-md"),
+                        PossibleValue::new("md"),
+                        // Binary cache format for machine consumers, kept out of --help
+                        PossibleValue::new("bincode").hide(true),
+                    ]))
+                    .env(FORMAT_ENV_VAR)
                     .default_value("yaml")
                     .help("Output format"),
             )
@@ -37,6 +52,51 @@ impl Command for NormalizeCommand {
                     .short('o')
                     .help("Output file (stdout if not specified)"),
             )
+            .arg(
+                Arg::new("known-types")
+                    .long("known-types")
+                    .value_name("FILE")
+                    .help("File listing extra known account type names, one per line"),
+            )
+            .arg(
+                Arg::new("warn-unknown-bodies")
+                    .long("warn-unknown-bodies")
+                    .action(ArgAction::SetTrue)
+                    .help("Report instructions whose body could not be analyzed"),
+            )
+            .arg(
+                Arg::new("error-on")
+                    .long("error-on")
+                    .value_name("RULE")
+                    .action(ArgAction::Append)
+                    .value_parser(PossibleValuesParser::new(RULES))
+                    .help("Treat issues from a validation rule as errors (repeatable)"),
+            )
+            .arg(
+                Arg::new("max-issues")
+                    .long("max-issues")
+                    .value_name("N")
+                    .value_parser(clap::value_parser!(usize))
+                    .help("Report at most N validation issues, most severe first"),
+            )
+            .arg(
+                Arg::new("accounts-only")
+                    .long("accounts-only")
+                    .action(ArgAction::SetTrue)
+                    .help("Output only the #[account] data layouts"),
+            )
+            .arg(
+                Arg::new("expect-schema-version")
+                    .long("expect-schema-version")
+                    .value_name("VERSION")
+                    .help("Fail instead of writing output if the schema version is not VERSION"),
+            )
+            .arg(
+                Arg::new("sort-output")
+                    .long("sort-output")
+                    .action(ArgAction::SetTrue)
+                    .help("Sort modules, accounts and fields by name instead of source order"),
+            )
             .arg(
                 Arg::new("verbose")
                     .short('v')
@@ -62,21 +122,130 @@ impl Command for NormalizeCommand {
         tracing::info!("Parsing file: {:?}", config.input_path);
         let program = anchor_parser::parse_file(&config.input_path)?;
 
+        // Build the normalizer configuration
+        let normalize_config = normalize_config(matches)?;
+
         // Normalize the parsed program
         tracing::info!("Normalizing program");
-        let normalized_program = anchor_normalizer::normalize(&program)?;
-
-        // Output the normalized model based on the configured format and destination
-        if let Some(output_path) = &config.output_path {
-            // Write to file
-            let mut file = File::create(output_path)?;
-            normalized_program.write_to(&mut file, &config.format)?;
-            tracing::info!("Normalized output written to {:?}", output_path);
+        let mut normalized_program =
+            anchor_normalizer::normalize_with_config(&program, &normalize_config)?;
+        let promoted = promote_issues(&mut normalized_program, matches);
+        if let Some(&max) = matches.get_one::<usize>("max-issues") {
+            truncate_issues(&mut normalized_program, max);
+        }
+        if matches.get_flag("sort-output") {
+            normalized_program.sort_by_name();
+        }
+
+        // Guard pinned consumers against an upgraded schema before any output
+        if let Some(expected) = matches.get_one::<String>("expect-schema-version") {
+            if *expected != normalized_program.schema_version {
+                return Err(Error::SchemaMismatch {
+                    expected: expected.clone(),
+                    found: normalized_program.schema_version.clone(),
+                });
+            }
+        }
+
+        // Output the normalized model, or just its raw accounts, based on the
+        // configured format and destination
+        if matches.get_flag("accounts-only") {
+            write_output(&normalized_program.raw_accounts, &config)?;
         } else {
-            // Write to stdout
-            normalized_program.write_to(&mut io::stdout(), &config.format)?;
+            write_output(&normalized_program, &config)?;
+        }
+
+        // Promoted issues fail the run once the output is written
+        if promoted > 0 {
+            return Err(Error::CheckFailed(format!(
+                "{} issues promoted to errors by --error-on",
+                promoted
+            )));
         }
 
         Ok(())
     }
 }
+
+/// Write a value to the configured output file, or stdout
+fn write_output(value: &impl Displayable, config: &Config) -> Result<(), Error> {
+    if let Some(output_path) = &config.output_path {
+        // Write to file
+        let mut file = File::create(output_path)?;
+        value.write_to(&mut file, &config.format)?;
+        tracing::info!("Normalized output written to {:?}", output_path);
+    } else {
+        // Write to stdout
+        value.write_to(&mut io::stdout(), &config.format)?;
+    }
+
+    Ok(())
+}
+
+/// Build the normalizer configuration from the shared normalize/check arguments
+pub(crate) fn normalize_config(
+    matches: &ArgMatches,
+) -> Result<anchor_normalizer::NormalizeConfig, Error> {
+    let mut config = anchor_normalizer::NormalizeConfig::new()
+        .with_warn_unknown_bodies(matches.get_flag("warn-unknown-bodies"));
+    if let Some(path) = matches.get_one::<String>("known-types") {
+        config = config.with_known_types(read_known_types(path)?);
+    }
+    Ok(config)
+}
+
+/// Promote issues raised by the `--error-on` rules to errors
+///
+/// Returns how many issues were promoted.
+pub(crate) fn promote_issues(program: &mut NormalizedProgram, matches: &ArgMatches) -> usize {
+    let Some(rules) = matches.get_many::<String>("error-on") else {
+        return 0;
+    };
+    let rules: Vec<&String> = rules.collect();
+
+    let mut promoted = 0;
+    for issue in &mut program.validation_issues {
+        if issue.severity != IssueSeverity::Error && rules.contains(&&issue.rule_id) {
+            issue.severity = IssueSeverity::Error;
+            promoted += 1;
+        }
+    }
+
+    // Keep the most severe issues first
+    program
+        .validation_issues
+        .sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    promoted
+}
+
+/// Keep only the `max` most severe validation issues
+///
+/// When issues are dropped, an info issue noting how many is appended. The
+/// note isn't raised by a validation rule, so its rule id is left empty.
+pub(crate) fn truncate_issues(program: &mut NormalizedProgram, max: usize) {
+    let issues = &mut program.validation_issues;
+    if issues.len() <= max {
+        return;
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    let omitted = issues.len() - max;
+    issues.truncate(max);
+    issues.push(ValidationIssue::info(
+        format!("{} more validation issues omitted", omitted),
+        program.name.clone(),
+    ));
+}
+
+/// Read known type names from a file, one per line
+///
+/// Blank lines and lines starting with `#` are ignored.
+fn read_known_types(path: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    let content = fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
@@ -1,11 +1,12 @@
 use super::Command;
-use crate::config::Config;
+use crate::config::{Config, FORMAT_ENV_VAR};
 use crate::error::Error;
 use crate::output::Displayable;
 use anchor_parser;
 use clap::{Arg, ArgAction, ArgMatches, Command as ClapCommand};
-use std::fs::File;
-use std::io;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
 
 pub struct ParseCommand;
 
@@ -17,12 +18,17 @@ impl Command for ParseCommand {
     fn build_subcommand(&self) -> ClapCommand {
         ClapCommand::new(self.name())
             .about("Parse Anchor code and output AST")
-            .arg(Arg::new("input").help("Input file to parse").required(true))
+            .arg(
+                Arg::new("input")
+                    .help("Input file to parse, or a directory to parse every .rs file in")
+                    .required(true),
+            )
             .arg(
                 Arg::new("format")
                     .long("format")
                     .short('f')
                     .value_parser(["yaml", "json", "debug"])
+                    .env(FORMAT_ENV_VAR)
                     .default_value("yaml")
                     .help("Output format"),
             )
@@ -32,6 +38,24 @@ impl Command for ParseCommand {
                     .short('o')
                     .help("Output file (stdout if not specified)"),
             )
+            .arg(
+                Arg::new("fail-fast")
+                    .long("fail-fast")
+                    .help("In directory mode, stop at the first file that fails to parse")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("stats-only")
+                    .long("stats-only")
+                    .help("Print item counts instead of the full model")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("sort-output")
+                    .long("sort-output")
+                    .action(ArgAction::SetTrue)
+                    .help("Sort modules, accounts and fields by name instead of source order"),
+            )
             .arg(
                 Arg::new("verbose")
                     .short('v')
@@ -53,9 +77,29 @@ impl Command for ParseCommand {
     fn execute(&self, matches: &ArgMatches) -> Result<(), Error> {
         let config = Config::from_matches(matches)?;
 
+        // A directory input parses every file and reports per-file results
+        if config.input_path.is_dir() {
+            let verbosity = matches.get_count("verbose");
+            return parse_batch(&config.input_path, matches.get_flag("fail-fast"), verbosity);
+        }
+
         // Parse the input file
         tracing::info!("Parsing file: {:?}", config.input_path);
-        let program = anchor_parser::parse_file(&config.input_path).map_err(Error::Parse)?;
+        let mut program = anchor_parser::parse_file(&config.input_path).map_err(Error::Parse)?;
+        if matches.get_flag("sort-output") {
+            program.sort_by_name();
+        }
+
+        // Counts are printed in place of the serialized model
+        if matches.get_flag("stats-only") {
+            let stats = format!("{}\n", program.counts());
+            if let Some(output_path) = &config.output_path {
+                fs::write(output_path, stats)?;
+            } else {
+                io::stdout().write_all(stats.as_bytes())?;
+            }
+            return Ok(());
+        }
 
         // Output the AST model based on the configured format and destination
         if let Some(output_path) = &config.output_path {
@@ -71,3 +115,49 @@ impl Command for ParseCommand {
         Ok(())
     }
 }
+
+/// Parse every `.rs` file below a directory, printing one result line per file
+///
+/// By default every file is attempted and the run fails afterwards if any
+/// file failed; with `fail_fast` it stops at the first failure. At
+/// verbosity 1 and above, a progress line is logged after each file.
+fn parse_batch(dir: &Path, fail_fast: bool, verbosity: u8) -> Result<(), Error> {
+    tracing::info!("Parsing directory: {:?}", dir);
+
+    let results = anchor_parser::parse_directory(dir)?;
+    let total = results.len();
+
+    let mut parsed = 0;
+    let mut failed = 0;
+    for (index, (path, result)) in results.enumerate() {
+        if verbosity >= 1 {
+            tracing::info!("parsed {}/{} files", index + 1, total);
+        }
+
+        match result {
+            Ok(_) => {
+                parsed += 1;
+                println!("ok: {}", path.display());
+            }
+            Err(err) => {
+                failed += 1;
+                println!("error: {}: {}", path.display(), err);
+                if fail_fast {
+                    return Err(Error::Parse(err));
+                }
+            }
+        }
+    }
+
+    println!("{} parsed, {} failed", parsed, failed);
+
+    if failed > 0 {
+        return Err(Error::BatchFailed(format!(
+            "{} of {} files failed to parse",
+            failed,
+            parsed + failed
+        )));
+    }
+
+    Ok(())
+}
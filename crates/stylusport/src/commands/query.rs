@@ -0,0 +1,81 @@
+use super::normalize::normalize_config;
+use super::Command;
+use crate::error::Error;
+use crate::output::query_path;
+use clap::{Arg, ArgAction, ArgMatches, Command as ClapCommand};
+use std::path::Path;
+
+pub struct QueryCommand;
+
+impl Command for QueryCommand {
+    fn name(&self) -> &'static str {
+        "query"
+    }
+
+    fn build_subcommand(&self) -> ClapCommand {
+        ClapCommand::new(self.name())
+            .about("Print one value from the normalized model by dotted path")
+            .arg(Arg::new("input").help("Input file to query").required(true))
+            .arg(
+                Arg::new("path")
+                    .help("Dotted path into the model (e.g., modules.0.instructions.2.name)")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("known-types")
+                    .long("known-types")
+                    .value_name("FILE")
+                    .help("File listing extra known account type names, one per line"),
+            )
+            .arg(
+                Arg::new("warn-unknown-bodies")
+                    .long("warn-unknown-bodies")
+                    .action(ArgAction::SetTrue)
+                    .help("Report instructions whose body could not be analyzed"),
+            )
+            .arg(
+                Arg::new("verbose")
+                    .short('v')
+                    .long("verbose")
+                    .action(ArgAction::Count)
+                    .value_parser(clap::value_parser!(u8))
+                    .help("Increase verbosity"),
+            )
+            .arg(
+                Arg::new("quiet")
+                    .short('q')
+                    .long("quiet")
+                    .help("Suppress all non-essential output")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("verbose"),
+            )
+    }
+
+    fn execute(&self, matches: &ArgMatches) -> Result<(), Error> {
+        let input_path = matches
+            .get_one::<String>("input")
+            .map(Path::new)
+            .ok_or_else(|| Error::MissingArgument("input".to_string()))?;
+        let path = matches
+            .get_one::<String>("path")
+            .ok_or_else(|| Error::MissingArgument("path".to_string()))?;
+
+        // Parse and normalize the input file
+        tracing::info!("Parsing file: {:?}", input_path);
+        let program = anchor_parser::parse_file(input_path)?;
+        let normalized_program =
+            anchor_normalizer::normalize_with_config(&program, &normalize_config(matches)?)?;
+
+        // Resolve the path against the serialized model
+        let model = serde_json::to_value(&normalized_program)?;
+        let value = query_path(&model, path).ok_or_else(|| Error::PathNotFound(path.clone()))?;
+
+        // Print strings bare, everything else as JSON
+        match value {
+            serde_json::Value::String(s) => println!("{}", s),
+            other => println!("{}", serde_json::to_string_pretty(other)?),
+        }
+
+        Ok(())
+    }
+}
@@ -9,6 +9,16 @@ pub enum OutputFormat {
     Yaml,
     Json,
     Debug,
+    /// TypeScript type definitions (normalized programs only)
+    Ts,
+    /// Per-account constraint matrix as CSV (normalized programs only)
+    MatrixCsv,
+    /// Per-account constraint matrix as Markdown tables (normalized programs only)
+    MatrixMd,
+    /// Markdown review report with per-instruction account tables (normalized programs only)
+    Markdown,
+    /// Compact binary encoding for machine consumers (normalized programs only)
+    Bincode,
 }
 
 impl FromStr for OutputFormat {
@@ -19,11 +29,21 @@ impl FromStr for OutputFormat {
             "yaml" => Ok(OutputFormat::Yaml),
             "json" => Ok(OutputFormat::Json),
             "debug" => Ok(OutputFormat::Debug),
+            "ts" => Ok(OutputFormat::Ts),
+            "matrix-csv" => Ok(OutputFormat::MatrixCsv),
+            "matrix-md" => Ok(OutputFormat::MatrixMd),
+            "md" => Ok(OutputFormat::Markdown),
+            "bincode" => Ok(OutputFormat::Bincode),
             _ => Err(Error::InvalidFormat(s.to_string())),
         }
     }
 }
 
+/// Environment variable naming the output format used when `--format` is not passed
+///
+/// Set as the `env` of each subcommand's `--format` argument.
+pub const FORMAT_ENV_VAR: &str = "STYLUSPORT_FORMAT";
+
 /// Configuration for command execution
 #[derive(Debug)]
 pub struct Config {
@@ -38,10 +58,10 @@ impl Config {
             .get_one::<String>("input")
             .ok_or_else(|| Error::MissingArgument("input".to_string()))?;
 
-        let output_path = matches
-            .get_one::<String>("output")
-            .map(|s| PathBuf::from(s));
+        let output_path = matches.get_one::<String>("output").map(PathBuf::from);
 
+        // Clap resolves --format, then FORMAT_ENV_VAR, then the default, and
+        // checks each against the subcommand's possible values
         let format = matches
             .get_one::<String>("format")
             .map(|s| OutputFormat::from_str(s))
@@ -23,6 +23,18 @@ pub enum Error {
 
     #[error("Serialization error: {0}")]
     Serialization(String),
+
+    #[error("Check failed: {0}")]
+    CheckFailed(String),
+
+    #[error("Batch failed: {0}")]
+    BatchFailed(String),
+
+    #[error("Path not found: {0}")]
+    PathNotFound(String),
+
+    #[error("Schema version mismatch: expected {expected}, found {found}")]
+    SchemaMismatch { expected: String, found: String },
 }
 
 // Implement conversions from other error types as needed
@@ -8,8 +8,10 @@ mod error;
 mod output;
 
 fn main() {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
+    // Initialize logging on stderr, keeping stdout for command output
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
     // Build and parse CLI arguments
     let matches = cli::build_cli().get_matches();
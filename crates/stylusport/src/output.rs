@@ -4,22 +4,305 @@ use serde::Serialize;
 use std::io::Write;
 
 /// Trait for types that can be displayed in different formats
+///
+/// YAML and JSON output has no trailing whitespace on any line and ends
+/// with exactly one newline.
 pub trait Displayable: Serialize + std::fmt::Debug {
     fn to_string(&self, format: &OutputFormat) -> Result<String, Error> {
         match format {
-            OutputFormat::Yaml => Ok(serde_yaml::to_string(self)?),
-            OutputFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            OutputFormat::Yaml => Ok(trim_whitespace(&serde_yaml::to_string(self)?)),
+            OutputFormat::Json => Ok(trim_whitespace(&serde_json::to_string_pretty(self)?)),
             OutputFormat::Debug => Ok(format!("{:#?}", self)),
+            OutputFormat::Ts => self
+                .to_typescript()
+                .ok_or_else(|| Error::InvalidFormat("ts".to_string())),
+            OutputFormat::MatrixCsv | OutputFormat::MatrixMd => self
+                .to_constraint_matrix(format)
+                .ok_or_else(|| Error::InvalidFormat("matrix".to_string())),
+            OutputFormat::Markdown => self
+                .to_markdown_report()
+                .ok_or_else(|| Error::InvalidFormat("md".to_string())),
+            // Binary output has no string form; see `write_to`
+            OutputFormat::Bincode => Err(Error::InvalidFormat("bincode".to_string())),
         }
     }
 
+    /// TypeScript type definitions, for types that support them
+    fn to_typescript(&self) -> Option<String> {
+        None
+    }
+
+    /// Constraint matrix in a matrix format, for types that support it
+    fn to_constraint_matrix(&self, _format: &OutputFormat) -> Option<String> {
+        None
+    }
+
+    /// Markdown review report, for types that support it
+    fn to_markdown_report(&self) -> Option<String> {
+        None
+    }
+
+    /// Compact binary encoding, for types that support it
+    fn to_bincode(&self) -> Result<Vec<u8>, Error> {
+        Err(Error::InvalidFormat("bincode".to_string()))
+    }
+
+    /// Write the formatted output
+    ///
+    /// JSON and YAML are serialized straight into the writer rather than
+    /// built up as an intermediate `String`, keeping memory flat for very
+    /// large programs. The output matches [`Displayable::to_string`].
     fn write_to<W: Write>(&self, writer: &mut W, format: &OutputFormat) -> Result<(), Error> {
-        let output = self.to_string(format)?;
-        writer.write_all(output.as_bytes()).map_err(Error::IO)
+        match format {
+            OutputFormat::Json => {
+                let mut writer = TrimWriter::new(writer);
+                serde_json::to_writer_pretty(&mut writer, self)?;
+                writer.finish().map_err(Error::IO)
+            }
+            OutputFormat::Yaml => {
+                let mut writer = TrimWriter::new(writer);
+                serde_yaml::to_writer(&mut writer, self)?;
+                writer.finish().map_err(Error::IO)
+            }
+            OutputFormat::Bincode => {
+                let bytes = self.to_bincode()?;
+                writer.write_all(&bytes).map_err(Error::IO)
+            }
+            _ => {
+                let output = self.to_string(format)?;
+                writer.write_all(output.as_bytes()).map_err(Error::IO)
+            }
+        }
+    }
+}
+
+/// Resolve a dotted path (e.g., `modules.0.instructions.2.name`) in a JSON value
+///
+/// Numeric segments index arrays; other segments look up object keys. An
+/// empty path resolves to the value itself.
+pub fn query_path<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            serde_json::Value::Array(items) => {
+                segment.parse::<usize>().ok().and_then(|i| items.get(i))
+            }
+            serde_json::Value::Object(fields) => fields.get(segment),
+            _ => None,
+        })
+}
+
+/// Strip trailing whitespace from each line and end with exactly one newline
+fn trim_whitespace(output: &str) -> String {
+    let mut trimmed: String = output
+        .trim_end()
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    trimmed.push('\n');
+    trimmed
+}
+
+/// A writer applying [`trim_whitespace`] to a stream
+///
+/// Whitespace and newlines are held back until the next visible byte, so
+/// they are dropped when they end a line or the output.
+/// [`TrimWriter::finish`] writes the final newline.
+struct TrimWriter<W: Write> {
+    inner: W,
+    pending_newlines: usize,
+    pending_spaces: Vec<u8>,
+}
+
+impl<W: Write> TrimWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending_newlines: 0,
+            pending_spaces: Vec::new(),
+        }
+    }
+
+    /// Write the single trailing newline and flush
+    fn finish(mut self) -> std::io::Result<()> {
+        self.inner.write_all(b"\n")?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Write for TrimWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut out = Vec::with_capacity(buf.len());
+        for &byte in buf {
+            match byte {
+                b'\n' => {
+                    self.pending_spaces.clear();
+                    self.pending_newlines += 1;
+                }
+                b' ' | b'\t' | b'\r' => self.pending_spaces.push(byte),
+                _ => {
+                    out.extend(std::iter::repeat_n(b'\n', self.pending_newlines));
+                    out.append(&mut self.pending_spaces);
+                    out.push(byte);
+                    self.pending_newlines = 0;
+                }
+            }
+        }
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
     }
 }
 
 // Implementation for Program types from anchor_parser
 impl Displayable for anchor_parser::Program {}
 // Implementation for NormalizedProgram from anchor_normalizer
-impl Displayable for anchor_normalizer::NormalizedProgram {}
+impl Displayable for anchor_normalizer::NormalizedProgram {
+    fn to_typescript(&self) -> Option<String> {
+        Some(anchor_normalizer::export::to_typescript(self))
+    }
+
+    fn to_markdown_report(&self) -> Option<String> {
+        Some(anchor_normalizer::export::to_markdown_report(self))
+    }
+
+    fn to_bincode(&self) -> Result<Vec<u8>, Error> {
+        Ok(anchor_normalizer::NormalizedProgram::to_bincode(self)?)
+    }
+
+    fn to_constraint_matrix(&self, format: &OutputFormat) -> Option<String> {
+        match format {
+            OutputFormat::MatrixCsv => {
+                Some(anchor_normalizer::export::to_constraint_matrix_csv(self))
+            }
+            OutputFormat::MatrixMd => Some(
+                anchor_normalizer::export::to_constraint_matrix_markdown(self),
+            ),
+            _ => None,
+        }
+    }
+}
+
+// Implementation for the raw account projection of a NormalizedProgram
+impl Displayable for Vec<anchor_normalizer::model::NormalizedRawAccount> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_normalizer::model::{
+        NormalizedAccountField, NormalizedAccountStruct, NormalizedInstruction, NormalizedModule,
+        NormalizedParameter, NormalizedProgram,
+    };
+
+    /// Build a program with many modules, instructions and accounts
+    fn large_program() -> NormalizedProgram {
+        let mut program = NormalizedProgram::new("program:large", "large");
+
+        for module_idx in 0..20 {
+            let mut module = NormalizedModule::new(format!("module_{}", module_idx), "pub");
+            for instruction_idx in 0..50 {
+                let context = format!("Context{}_{}", module_idx, instruction_idx);
+                let mut instruction =
+                    NormalizedInstruction::new(format!("instruction_{}", instruction_idx), "pub")
+                        .with_account_struct(&context);
+                instruction.add_parameter(NormalizedParameter::new_context("ctx", &context));
+                instruction.add_parameter(NormalizedParameter::new("amount", "u64", false));
+                module.add_instruction(instruction);
+
+                let mut account = NormalizedAccountStruct::new(context, "pub");
+                account.add_field(NormalizedAccountField::new("authority", "Signer<'info>"));
+                program.add_account_struct(account);
+            }
+            program.add_module(module);
+        }
+
+        program
+    }
+
+    #[test]
+    fn test_streamed_output_matches_string_output() {
+        let program = large_program();
+
+        for format in [
+            OutputFormat::Json,
+            OutputFormat::Yaml,
+            OutputFormat::Ts,
+            OutputFormat::MatrixCsv,
+        ] {
+            let mut streamed = Vec::new();
+            program.write_to(&mut streamed, &format).unwrap();
+
+            let buffered = program.to_string(&format).unwrap();
+            assert_eq!(
+                streamed,
+                buffered.as_bytes(),
+                "{:?} output should match",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_output_whitespace_is_normalized() {
+        let program = large_program();
+
+        for format in [OutputFormat::Json, OutputFormat::Yaml] {
+            let mut output = Vec::new();
+            program.write_to(&mut output, &format).unwrap();
+            let output = String::from_utf8(output).unwrap();
+
+            assert!(
+                output.ends_with('\n'),
+                "{:?} should end with a newline",
+                format
+            );
+            assert!(
+                !output.ends_with("\n\n"),
+                "{:?} should end with exactly one newline",
+                format
+            );
+            assert!(
+                output.lines().all(|line| line == line.trim_end()),
+                "{:?} should have no trailing whitespace",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_query_path() {
+        let value = serde_json::json!({
+            "modules": [{"name": "vault", "instructions": [{"name": "deposit"}]}],
+        });
+
+        assert_eq!(query_path(&value, ""), Some(&value));
+        assert_eq!(
+            query_path(&value, "modules.0.instructions.0.name"),
+            Some(&serde_json::json!("deposit"))
+        );
+        assert_eq!(query_path(&value, "modules.1"), None);
+        assert_eq!(query_path(&value, "modules.first"), None);
+        assert_eq!(query_path(&value, "modules.0.name.length"), None);
+    }
+
+    #[test]
+    fn test_trim_writer() {
+        let mut output = Vec::new();
+        let mut writer = TrimWriter::new(&mut output);
+        // Split writes so whitespace spans chunk boundaries
+        for chunk in ["a:  ", " \n  b: 1 ", "\t\n\n  c\n", "\n \n"] {
+            writer.write_all(chunk.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        assert_eq!(output, b"a:\n  b: 1\n\n  c\n");
+        assert_eq!(
+            trim_whitespace("a:   \n  b: 1 \t\n\n  c\n\n \n"),
+            "a:\n  b: 1\n\n  c\n"
+        );
+    }
+}
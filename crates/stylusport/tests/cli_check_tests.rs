@@ -0,0 +1,146 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// Helper to get the path to test fixtures
+fn fixture_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../examples");
+    path.push(name);
+    path.push("lib.rs");
+    path
+}
+
+/// Split the command output into lines
+fn report_lines(stdout: &[u8]) -> Vec<String> {
+    String::from_utf8(stdout.to_vec())
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn test_check_clean_program() {
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("check")
+        .arg(fixture_path("hello_world").to_str().unwrap())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Check should succeed");
+    assert_eq!(
+        report_lines(&output.stdout).last().map(String::as_str),
+        Some("0 errors, 0 warnings, 0 info")
+    );
+}
+
+#[test]
+fn test_check_reports_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    let source_file = temp_dir.path().join("lib.rs");
+    fs::write(
+        &source_file,
+        r#"
+        use anchor_lang::prelude::*;
+
+        #[program]
+        pub mod broken {
+            use super::*;
+
+            pub fn touch(ctx: Context<Touch>) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct Touch<'info> {
+            pub thing: Account<'info, ExternalThing>,
+        }
+
+        #[derive(Accounts)]
+        pub struct Touch<'info> {
+            pub other: Signer<'info>,
+        }
+        "#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("check")
+        .arg(source_file.to_str().unwrap())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success(), "Errors should fail the check");
+
+    let lines = report_lines(&output.stdout);
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("error: Duplicate account struct name: Touch")));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("warning:") && line.contains("ExternalThing")));
+    assert_eq!(
+        lines.last().map(String::as_str),
+        Some("1 error, 1 warning, 2 info")
+    );
+}
+
+#[test]
+fn test_check_error_on_rule() {
+    let temp_dir = TempDir::new().unwrap();
+    let source_file = temp_dir.path().join("lib.rs");
+    fs::write(
+        &source_file,
+        r#"
+        use anchor_lang::prelude::*;
+
+        #[program]
+        pub mod registry {
+            use super::*;
+
+            pub fn state(ctx: Context<State>) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct State<'info> {
+            pub authority: Signer<'info>,
+        }
+        "#,
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::cargo_bin("stylusport")
+            .unwrap()
+            .arg("check")
+            .arg(source_file.to_str().unwrap())
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    // The reserved name is only a warning by default
+    let output = run(&[]);
+    assert!(output.status.success(), "Warnings alone should pass");
+
+    let output = run(&["--error-on", "naming"]);
+    assert!(
+        !output.status.success(),
+        "Promoted naming issues should fail the check"
+    );
+    let lines = report_lines(&output.stdout);
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("error: Instruction state uses a name reserved by Anchor")));
+
+    // Other rules leave the naming warning alone
+    let output = run(&["--error-on", "unused-struct"]);
+    assert!(output.status.success());
+}
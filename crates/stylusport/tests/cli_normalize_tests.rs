@@ -118,14 +118,7 @@ fn test_normalize_file_yaml() {
 
     assert!(output.status.success(), "Normalization should succeed");
 
-    let stdout = String::from_utf8(output.stdout).unwrap();
-
-    // Remove the log line
-    let yaml_content = stdout
-        .lines()
-        .filter(|line| !line.contains("INFO"))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let yaml_content = String::from_utf8(output.stdout).unwrap();
 
     // For debugging
     println!("Raw YAML content:\n{}", yaml_content);
@@ -155,14 +148,7 @@ fn test_normalize_file_json() {
 
     assert!(output.status.success(), "Normalization should succeed");
 
-    let stdout = String::from_utf8(output.stdout).unwrap();
-
-    // Remove the log line
-    let json_content = stdout
-        .lines()
-        .filter(|line| !line.contains("INFO"))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let json_content = String::from_utf8(output.stdout).unwrap();
 
     // For debugging
     println!("Raw JSON content:\n{}", json_content);
@@ -178,6 +164,241 @@ fn test_normalize_file_json() {
     insta::assert_snapshot!(json_content);
 }
 
+#[test]
+fn test_normalize_file_ts() {
+    let fixture_path = fixture_path("token_vault");
+
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .arg("--format=ts")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Normalization should succeed");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("export interface Vault {"));
+    assert!(stdout.contains("authority: PublicKey;"));
+}
+
+#[test]
+fn test_normalize_format_from_env() {
+    let fixture_path = fixture_path("hello_world");
+
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .env("STYLUSPORT_FORMAT", "json")
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Normalization should succeed");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let value: serde_json::Value =
+        serde_json::from_str(&stdout).expect("STYLUSPORT_FORMAT=json should produce JSON");
+    assert_eq!(value["name"], "hello_world");
+
+    // An explicit --format still wins
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .env("STYLUSPORT_FORMAT", "json")
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .arg("--format=yaml")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(serde_json::from_str::<serde_json::Value>(&stdout).is_err());
+    assert!(stdout.contains("name: hello_world"));
+
+    // Unknown formats are rejected
+    Command::cargo_bin("stylusport")
+        .unwrap()
+        .env("STYLUSPORT_FORMAT", "xml")
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_parse_rejects_env_format_for_other_subcommand() {
+    let fixture_path = fixture_path("hello_world");
+
+    // `ts` is a normalize format, so parse rejects it before parsing
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .env("STYLUSPORT_FORMAT", "ts")
+        .arg("parse")
+        .arg(fixture_path.to_str().unwrap())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success(), "Parse should reject the format");
+    assert!(
+        output.stdout.is_empty(),
+        "Nothing should be parsed or written"
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("invalid value 'ts'"), "stderr: {}", stderr);
+
+    // The same value is accepted by normalize
+    Command::cargo_bin("stylusport")
+        .unwrap()
+        .env("STYLUSPORT_FORMAT", "ts")
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_normalize_accounts_only() {
+    let fixture_path = fixture_path("token_program");
+
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("normalize")
+        .arg(fixture_path.to_str().unwrap())
+        .arg("--format=json")
+        .arg("--accounts-only")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Normalization should succeed");
+
+    let json_content = String::from_utf8(output.stdout).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+    let accounts = value
+        .as_array()
+        .expect("output should be the raw accounts array");
+    let names: Vec<&str> = accounts
+        .iter()
+        .filter_map(|account| account["name"].as_str())
+        .collect();
+    assert_eq!(names, ["TokenMint", "Wallet"]);
+
+    let mint = accounts
+        .iter()
+        .find(|account| account["name"] == "TokenMint")
+        .expect("TokenMint should be emitted");
+    assert_eq!(mint["derives_init_space"], true);
+    assert!(!json_content.contains("\"modules\""));
+}
+
+#[test]
+fn test_normalize_sort_output() {
+    let account_struct_names = |extra_args: &[&str]| {
+        let output = Command::cargo_bin("stylusport")
+            .unwrap()
+            .arg("normalize")
+            .arg(fixture_path("token_vault").to_str().unwrap())
+            .arg("--format=json")
+            .args(extra_args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "Normalization should succeed");
+
+        let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        value["account_structs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|account| account["name"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Source order by default, alphabetical with --sort-output
+    assert_eq!(account_struct_names(&[]), ["Initialize", "Deposit"]);
+    assert_eq!(
+        account_struct_names(&["--sort-output"]),
+        ["Deposit", "Initialize"]
+    );
+}
+
+#[test]
+fn test_normalize_expect_schema_version() {
+    let normalize = |version: &str| {
+        Command::cargo_bin("stylusport")
+            .unwrap()
+            .arg("normalize")
+            .arg(fixture_path("counter").to_str().unwrap())
+            .arg("--format=json")
+            .arg("--expect-schema-version")
+            .arg(version)
+            .output()
+            .unwrap()
+    };
+
+    let output = normalize(anchor_normalizer::model::SCHEMA_VERSION);
+    assert!(output.status.success(), "Matching version should succeed");
+    assert!(!output.stdout.is_empty());
+
+    let output = normalize("0.1");
+    assert!(!output.status.success(), "Mismatched version should fail");
+    assert!(output.stdout.is_empty(), "No output on a mismatch");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("expected 0.1, found"), "{}", stderr);
+}
+
+#[test]
+fn test_normalize_max_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    let source_file = temp_dir.path().join("lib.rs");
+    fs::write(
+        &source_file,
+        r#"
+        use anchor_lang::prelude::*;
+
+        #[program]
+        pub mod broken {
+            use super::*;
+
+            pub fn touch(ctx: Context<Touch>) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct Touch<'info> {
+            pub first: Account<'info, MissingOne>,
+            pub second: Account<'info, MissingTwo>,
+            pub third: Account<'info, MissingThree>,
+            pub fourth: Account<'info, MissingFour>,
+        }
+        "#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("normalize")
+        .arg(source_file.to_str().unwrap())
+        .arg("--format=json")
+        .arg("--max-issues")
+        .arg("2")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Normalization should succeed");
+
+    let json_content = String::from_utf8(output.stdout).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+    let issues = value["validation_issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 3, "two issues plus the omitted note");
+    assert_eq!(
+        issues[2]["message"].as_str(),
+        Some("6 more validation issues omitted")
+    );
+    assert_eq!(issues[2]["rule_id"].as_str(), Some(""));
+}
+
 #[test]
 fn test_normalize_invalid_file() {
     // Create a temporary directory for our invalid file
@@ -216,3 +437,59 @@ fn test_normalize_invalid_file() {
         "Expected error output in either stdout or stderr, but both were empty"
     );
 }
+
+#[test]
+fn test_normalize_known_types_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let source_file = temp_dir.path().join("lib.rs");
+    fs::write(
+        &source_file,
+        r#"
+        use anchor_lang::prelude::*;
+
+        #[program]
+        pub mod external {
+            use super::*;
+
+            pub fn touch(ctx: Context<Touch>) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        #[derive(Accounts)]
+        pub struct Touch<'info> {
+            pub thing: Account<'info, ExternalThing>,
+        }
+        "#,
+    )
+    .unwrap();
+
+    let known_types_file = temp_dir.path().join("types.txt");
+    fs::write(&known_types_file, "# external crate types\nExternalThing\n").unwrap();
+
+    // Without the allow-list the external type is flagged
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("normalize")
+        .arg(source_file.to_str().unwrap())
+        .arg("--format=json")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Normalization should succeed");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("unknown account type ExternalThing"));
+
+    // With the allow-list it is no longer warned about
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("normalize")
+        .arg(source_file.to_str().unwrap())
+        .arg("--format=json")
+        .arg("--known-types")
+        .arg(known_types_file.to_str().unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Normalization should succeed");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("unknown account type ExternalThing"));
+}
@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+/// Helper to get the path to test fixtures
+fn fixture_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../examples");
+    path.push(name);
+    path.push("lib.rs");
+    path
+}
+
+/// Get the command output without its trailing newline
+fn query_output(stdout: &[u8]) -> String {
+    String::from_utf8(stdout.to_vec())
+        .unwrap()
+        .trim_end()
+        .to_string()
+}
+
+#[test]
+fn test_query_instructions() {
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("query")
+        .arg(fixture_path("token_program").to_str().unwrap())
+        .arg("modules.0.instructions")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Query should succeed");
+    let value: serde_json::Value = serde_json::from_str(&query_output(&output.stdout)).unwrap();
+    let instructions = value.as_array().expect("instructions should be an array");
+    assert_eq!(instructions.len(), 3);
+}
+
+#[test]
+fn test_query_scalar() {
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("query")
+        .arg(fixture_path("token_program").to_str().unwrap())
+        .arg("modules.0.instructions.2.name")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Query should succeed");
+    assert_eq!(query_output(&output.stdout), "transfer");
+}
+
+#[test]
+fn test_query_missing_path() {
+    Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("query")
+        .arg(fixture_path("token_program").to_str().unwrap())
+        .arg("modules.5")
+        .assert()
+        .failure();
+}
@@ -35,14 +35,7 @@ fn test_parse_file_yaml() {
 
     assert!(output.status.success(), "Parsing should succeed");
 
-    let stdout = String::from_utf8(output.stdout).unwrap();
-
-    // Remove the log line
-    let yaml_content = stdout
-        .lines()
-        .filter(|line| !line.contains("INFO"))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let yaml_content = String::from_utf8(output.stdout).unwrap();
 
     // Debugging: Try to parse without strict validation first
     let parsed: serde_yaml::Value =
@@ -75,14 +68,7 @@ fn test_parse_file_json() {
 
     assert!(output.status.success(), "Parsing should succeed");
 
-    let stdout = String::from_utf8(output.stdout).unwrap();
-
-    // Remove the log line
-    let json_content = stdout
-        .lines()
-        .filter(|line| !line.contains("INFO"))
-        .collect::<Vec<_>>()
-        .join("\n");
+    let json_content = String::from_utf8(output.stdout).unwrap();
 
     // Parse JSON
     let parsed: serde_json::Value =
@@ -133,3 +119,103 @@ fn test_parse_invalid_file() {
         "Error message should indicate parsing failure"
     );
 }
+
+#[test]
+fn test_parse_directory_fail_fast() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a_invalid.rs"),
+        "this is not valid rust code",
+    )
+    .unwrap();
+    fs::copy(
+        fixture_path("hello_world"),
+        temp_dir.path().join("b_valid.rs"),
+    )
+    .unwrap();
+
+    let run = |fail_fast: bool| {
+        let mut command = Command::cargo_bin("stylusport").unwrap();
+        command.arg("parse").arg(temp_dir.path().to_str().unwrap());
+        if fail_fast {
+            command.arg("--fail-fast");
+        }
+        let output = command.output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        (output.status.success(), stdout)
+    };
+
+    // By default every file is attempted and each result reported
+    let (success, stdout) = run(false);
+    assert!(!success, "A failed file should fail the batch");
+    assert!(stdout.contains("error: ") && stdout.contains("a_invalid.rs"));
+    assert!(stdout.contains("ok: ") && stdout.contains("b_valid.rs"));
+    assert!(stdout.contains("1 parsed, 1 failed"));
+
+    // With --fail-fast the run stops at the first failure
+    let (success, stdout) = run(true);
+    assert!(!success, "--fail-fast should fail");
+    assert!(stdout.contains("a_invalid.rs"));
+    assert!(
+        !stdout.contains("b_valid.rs"),
+        "Later files should be skipped"
+    );
+}
+
+#[test]
+fn test_parse_directory_progress() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["a.rs", "b.rs", "c.rs"] {
+        fs::copy(fixture_path("hello_world"), temp_dir.path().join(name)).unwrap();
+    }
+
+    let run = |verbose: bool| {
+        let mut command = Command::cargo_bin("stylusport").unwrap();
+        command.arg("parse").arg(temp_dir.path().to_str().unwrap());
+        if verbose {
+            command.arg("-v");
+        }
+        let output = command.output().unwrap();
+        assert!(output.status.success(), "Batch parse should succeed");
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+        )
+    };
+
+    let (stdout, stderr) = run(true);
+    assert!(stderr.contains("parsed 1/3 files"));
+    assert!(stderr.contains("parsed 3/3 files"));
+    assert!(
+        !stdout.contains("parsed 1/3 files"),
+        "Progress should stay off stdout"
+    );
+    assert!(stdout.contains("3 parsed, 0 failed"));
+
+    let (_, stderr) = run(false);
+    assert!(!stderr.contains("parsed 1/3 files"));
+}
+
+#[test]
+fn test_parse_stats_only() {
+    let output = Command::cargo_bin("stylusport")
+        .unwrap()
+        .arg("parse")
+        .arg(fixture_path("token_vault").to_str().unwrap())
+        .arg("--stats-only")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "Parse should succeed");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stats: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        stats,
+        vec![
+            "program modules: 1",
+            "instructions: 2",
+            "account structs: 2",
+            "raw accounts: 1",
+        ]
+    );
+}
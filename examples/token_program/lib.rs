@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+#[program]
+pub mod token_program {
+    use super::*;
+
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let mint = &mut ctx.accounts.mint;
+        mint.authority = ctx.accounts.authority.key();
+        mint.supply = 0;
+        Ok(())
+    }
+
+    pub fn mint(ctx: Context<MintTo>, amount: u64) -> Result<()> {
+        ctx.accounts.mint.supply += amount;
+        ctx.accounts.to.amount += amount;
+        Ok(())
+    }
+
+    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+        ctx.accounts.from.amount -= amount;
+        ctx.accounts.to.amount += amount;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(init, payer = authority, space = 8 + TokenMint::INIT_SPACE)]
+    pub mint: Account<'info, TokenMint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintTo<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub mint: Account<'info, TokenMint>,
+
+    #[account(mut)]
+    pub to: Account<'info, Wallet>,
+}
+
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub from: Account<'info, Wallet>,
+
+    #[account(mut)]
+    pub to: Account<'info, Wallet>,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct TokenMint {
+    pub authority: Pubkey,
+    pub supply: u64,
+}
+
+#[account]
+pub struct Wallet {
+    pub owner: Pubkey,
+    pub amount: u64,
+}